@@ -0,0 +1,335 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, is_separator},
+};
+
+use chrono::{DateTime, Local, format::{Item, StrftimeItems}};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Cell, Clear, Paragraph, Row, Table},
+};
+use regex::Regex;
+
+use crate::{App, color_theme::ColorTheme, dependencies::rename_all, explorer::{Explorer, FileEntry}, undo::Operation};
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseConversion {
+    Upper,
+    Lower,
+    Title,
+}
+
+/// Parsed `rename <FIND> <REPLACE> [upper|lower|title]` command
+pub struct RenameSpec {
+    find: Regex,
+    replace: String,
+    case: Option<CaseConversion>,
+}
+
+/// A line of the preview -> Old name, new name and what is wrong with the new name, if anything
+pub struct RenamePreviewRow {
+    pub old: String,
+    pub new: String,
+    pub problem: Option<String>,
+}
+
+// Splits the arguments on spaces. Double quotes keep spaces in an argument and allow empty arguments.
+pub fn split_args(args: &str) -> Vec<String> {
+    let mut split: Vec<String> = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut started = false;
+    for c in args.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                started = true;
+            },
+            ' ' if !in_quotes => {
+                if started {
+                    split.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            },
+            _ => {
+                current.push(c);
+                started = true;
+            },
+        }
+    }
+    if started {
+        split.push(current);
+    }
+    split
+}
+
+/// Parses the arguments following `rename`
+pub fn parse_rename_args(args: &str) -> Result<RenameSpec, String> {
+    let split = split_args(args);
+    let (Some(find), Some(replace)) = (split.first(), split.get(1)) else {
+        return Err(String::from("Usage: rename <FIND> <REPLACE> [upper|lower|title]"));
+    };
+    let case = match split.get(2).map(|arg| arg.as_str()) {
+        None => None,
+        Some("upper") => Some(CaseConversion::Upper),
+        Some("lower") => Some(CaseConversion::Lower),
+        Some("title") => Some(CaseConversion::Title),
+        Some(other) => return Err(format!("Unknown case conversion: {other}")),
+    };
+    let find = Regex::new(find).map_err(|err| format!("Invalid pattern: {err}"))?;
+    check_date_formats(replace)?;
+    Ok(RenameSpec { find, replace: replace.clone(), case })
+}
+
+fn title_case(name: &str) -> String {
+    let mut titled = String::with_capacity(name.len());
+    let mut word_start = true;
+    for c in name.chars() {
+        if word_start {
+            titled.extend(c.to_uppercase());
+        } else {
+            titled.extend(c.to_lowercase());
+        }
+        word_start = matches!(c, ' ' | '_' | '-' | '.');
+    }
+    titled
+}
+
+// chrono panics while formatting with an unknown specifier, the formats are checked before
+fn is_valid_date_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| item == Item::Error)
+}
+
+// Every `{date:FORMAT}` token of the replacement must have a format chrono knows
+fn check_date_formats(replace: &str) -> Result<(), String> {
+    let mut rest = replace;
+    while let Some(start) = rest.find("{date:") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let format = &rest[start + "{date:".len()..start + len];
+        if !is_valid_date_format(format) {
+            return Err(format!("Invalid date format: {format}"));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    Ok(())
+}
+
+// Replaces the `{n}`, `{n:03}`, `{ext}`, `{date}` and `{date:FORMAT}` tokens of the replacement.
+// `$` in the values is escaped, as the result is used as a regex replacement.
+fn expand_tokens(replace: &str, counter: usize, entry: &FileEntry) -> String {
+    let mut expanded = String::with_capacity(replace.len());
+    let mut rest = replace;
+    while let Some(start) = rest.find('{') {
+        // An unclosed brace is kept as it is, with the rest
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        let token = &rest[start + 1..start + len];
+        let (name, format) = match token.split_once(':') {
+            Some((name, format)) => (name, Some(format)),
+            None => (token, None),
+        };
+        let value = match name {
+            "n" => match format.and_then(|width| width.parse::<usize>().ok()) {
+                Some(width) if format.is_some_and(|width| width.starts_with('0')) => format!("{counter:0width$}"),
+                Some(width) => format!("{counter:width$}"),
+                None => counter.to_string(),
+            },
+            "ext" => Path::new(&entry.name).extension().map(|ext| ext.to_string_lossy().to_string()).unwrap_or_default(),
+            "date" if !format.is_none_or(is_valid_date_format) => rest[start..=start + len].to_string(),
+            "date" => entry
+                .modified
+                .map(|modified| {
+                    let date: DateTime<Local> = modified.into();
+                    format!("{}", date.format(format.unwrap_or(DEFAULT_DATE_FORMAT)))
+                })
+                .unwrap_or_default(),
+            // Not a token, kept as it is
+            _ => rest[start..=start + len].to_string(),
+        };
+        expanded.push_str(&value.replace('$', "$$"));
+        rest = &rest[start + len + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+impl RenameSpec {
+    /// New name for `entry`, or `None` if the pattern doesn't match it
+    fn new_name(&self, entry: &FileEntry, counter: usize) -> Option<String> {
+        if !self.find.is_match(&entry.name) {
+            return None;
+        }
+        let replace = expand_tokens(&self.replace, counter, entry);
+        let renamed = self.find.replace_all(&entry.name, replace.as_str()).to_string();
+        Some(match self.case {
+            Some(CaseConversion::Upper) => renamed.to_uppercase(),
+            Some(CaseConversion::Lower) => renamed.to_lowercase(),
+            Some(CaseConversion::Title) => title_case(&renamed),
+            None => renamed,
+        })
+    }
+}
+
+/// Old -> new names of the entries of the Explorer matched by the pattern (only the marked ones, if any are marked)
+pub fn preview_renames(explorer: &Explorer, spec: &RenameSpec) -> Vec<RenamePreviewRow> {
+    let mut rows: Vec<RenamePreviewRow> = vec![];
+    let mut counter = 0;
+    for entry in &explorer.files {
+        if !explorer.marked.is_empty() && !explorer.is_marked(entry) {
+            continue;
+        }
+        if let Some(new) = spec.new_name(entry, counter + 1) {
+            counter += 1;
+            rows.push(RenamePreviewRow { old: entry.name.clone(), new, problem: None });
+        }
+    }
+    // Names the renamed entries are leaving, they can be taken by another one
+    let vacated: HashSet<String> = rows.iter().filter(|row| row.old != row.new).map(|row| row.old.clone()).collect();
+    let mut taken: HashMap<String, usize> = HashMap::new();
+    for row in &rows {
+        *taken.entry(row.new.clone()).or_default() += 1;
+    }
+    for row in rows.iter_mut() {
+        row.problem = if row.new.is_empty() || row.new == "." || row.new == ".." || row.new.contains(is_separator) {
+            Some(String::from("invalid name"))
+        } else if taken.get(&row.new).is_some_and(|count| *count > 1) {
+            Some(String::from("duplicate name"))
+        } else if row.old != row.new && explorer.root_path.join(&row.new).symlink_metadata().is_ok() && !vacated.contains(&row.new) {
+            Some(String::from("already exists"))
+        } else {
+            None
+        };
+    }
+    rows
+}
+
+/// Preview shown above the Command bar while a `rename` command is typed
+pub fn render_rename_preview(args: &str, explorer: &Explorer, color_theme: &ColorTheme, frame: &mut Frame, area: Rect) {
+    frame.render_widget(Clear, area);
+    let block = Block::bordered()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .title(" Rename preview ")
+        .title_style(Style::new().fg(color_theme.primary))
+        .border_style(Style::new().fg(color_theme.border));
+    let spec = match parse_rename_args(args) {
+        Ok(spec) => spec,
+        Err(err) => {
+            frame.render_widget(Paragraph::new(format!(" {err}")).bg(Color::Black).block(block), area);
+            return;
+        },
+    };
+    let rows = preview_renames(explorer, &spec);
+    let conflicts = rows.iter().filter(|row| row.problem.is_some()).count();
+    let status = if conflicts > 0 {
+        Line::from(format!(" {} item(s), {} conflict(s) ", rows.len(), conflicts)).red()
+    } else {
+        Line::from(format!(" {} item(s) ", rows.len()))
+    };
+    let table_rows = rows.iter().map(|row| {
+        let style = if row.problem.is_some() {
+            Style::default().fg(Color::Red)
+        } else if row.old == row.new {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().fg(color_theme.primary)
+        };
+        Row::new([
+            Cell::from(row.old.clone()),
+            Cell::from("→"),
+            Cell::from(row.new.clone()),
+            Cell::from(row.problem.clone().unwrap_or_default()),
+        ])
+        .style(style)
+    });
+    let table = Table::new(table_rows, [Constraint::Percentage(40), Constraint::Length(1), Constraint::Percentage(40), Constraint::Min(10)])
+        .block(block.title_bottom(status))
+        .bg(Color::Black);
+    frame.render_widget(table, area);
+}
+
+/// Applies the `rename` command. Nothing is renamed while any of the new names is in conflict.
+pub fn apply_batch_rename(app: &mut App, args: &str) -> Result<(), String> {
+    let spec = parse_rename_args(args)?;
+    let rows = preview_renames(&app.explorer, &spec);
+    let conflicts: Vec<String> = rows
+        .iter()
+        .filter_map(|row| row.problem.as_ref().map(|problem| format!("{} ({problem})", row.new)))
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(format!("Nothing was renamed, conflicts: {}", conflicts.join(", ")));
+    }
+    let pairs: Vec<_> = rows
+        .iter()
+        .filter(|row| row.old != row.new)
+        .map(|row| (app.explorer.root_path.join(&row.old), app.explorer.root_path.join(&row.new)))
+        .collect();
+    let (renamed, result) = rename_all(&pairs);
+    if !renamed.is_empty() {
+        app.undo.record(Operation::Rename(renamed.clone()));
+    }
+    app.explorer.marked.clear();
+    app.explorer.reload();
+    result?;
+    app.log_panel.set_log(format!("Renamed {} item(s)", renamed.len()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::{expand_tokens, parse_rename_args};
+    use crate::explorer::{EntryType, FileEntry};
+
+    fn entry(name: &str) -> FileEntry {
+        FileEntry {
+            e_type: EntryType::File,
+            name: name.to_string(),
+            size: String::new(),
+            size_bytes: 0,
+            modified_at: String::new(),
+            modified: Some(SystemTime::now()),
+            created: None,
+            accessed: None,
+            hidden: false,
+            is_exec: false,
+            mode: None,
+            owner: None,
+            inode: None,
+            links: None,
+            link: None,
+        }
+    }
+
+    #[test]
+    fn expands_tokens() {
+        assert_eq!(expand_tokens("{n:03}_{n}.{ext}", 7, &entry("a.txt")), "007_7.txt");
+        assert_eq!(expand_tokens("{date:%%}", 1, &entry("a")), "%");
+        assert_eq!(expand_tokens("{other}", 1, &entry("a")), "{other}");
+    }
+
+    #[test]
+    fn keeps_an_unclosed_brace() {
+        assert_eq!(expand_tokens("ab{c", 1, &entry("a")), "ab{c");
+        assert_eq!(expand_tokens("{n}-{n", 2, &entry("a")), "2-{n");
+    }
+
+    #[test]
+    fn refuses_an_invalid_date_format() {
+        let err = parse_rename_args("a {date:%Q}").err().unwrap();
+        assert_eq!(err, "Invalid date format: %Q");
+        assert!(parse_rename_args("a {date:%").is_ok());
+        assert!(parse_rename_args("a {date:%Y}_{date}").is_ok());
+        // Never formatted, even when the check is bypassed
+        assert_eq!(expand_tokens("{date:%Q}", 1, &entry("a")), "{date:%Q}");
+    }
+}
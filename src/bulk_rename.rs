@@ -0,0 +1,181 @@
+use std::{
+    collections::HashSet,
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{PathBuf, is_separator},
+    process::Command,
+    sync::atomic::Ordering,
+    thread,
+    time::Duration,
+};
+
+use ratatui::DefaultTerminal;
+
+use crate::{App, dependencies::rename_all, mouse::{disable_mouse_capture, enable_mouse_capture}, undo::Operation};
+
+// $VISUAL, then $EDITOR, then a default editor of the platform
+fn editor_command() -> Vec<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| String::from(if cfg!(target_os = "windows") { "notepad" } else { "vi" }));
+    editor.split_whitespace().map(String::from).collect()
+}
+
+// Runs the editor on `file` with the TUI out of the way
+fn edit_in_terminal(app: &mut App, terminal: &mut DefaultTerminal, file: &PathBuf) -> Result<(), String> {
+    let editor = editor_command();
+    let Some((program, args)) = editor.split_first() else {
+        return Err(String::from("No editor is set in $EDITOR"));
+    };
+    // The input thread must not take the keys meant for the editor
+    app.input_paused.store(true, Ordering::Relaxed);
+    thread::sleep(Duration::from_millis(50));
+    disable_mouse_capture();
+    ratatui::restore();
+    let status = Command::new(program).args(args).arg(file).status();
+    *terminal = ratatui::init();
+    enable_mouse_capture();
+    let _ = terminal.clear();
+    app.input_paused.store(false, Ordering::Relaxed);
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("The editor exited with {status}, nothing was renamed")),
+        Err(err) => Err(format!("Unable to start the editor {program}: {err}")),
+    }
+}
+
+// Writes the names to a new file of the temp directory, readable by the user only. The file is always created anew,
+// so nothing planted at its name (like a symlink) is followed or written to.
+fn create_names_file(contents: &str) -> Result<PathBuf, String> {
+    for attempt in 0..100 {
+        let path = env::temp_dir().join(format!("columbus-rename-{}-{attempt}.txt", std::process::id()));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(mut file) => {
+                file.write_all(contents.as_bytes()).map_err(|err| format!("Unable to write {}: {err}", path.to_string_lossy()))?;
+                return Ok(path);
+            },
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(format!("Unable to create {}: {err}", path.to_string_lossy())),
+        }
+    }
+    Err(String::from("Unable to create a file for the names in the temp directory"))
+}
+
+// Pairs the old paths with the edited names. Nothing is renamed if any of the names is not usable.
+fn plan_renames(paths: &[PathBuf], edited: &str) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let names: Vec<&str> = edited.trim_end_matches(['\n', '\r']).lines().collect();
+    if names.len() != paths.len() {
+        return Err(format!(
+            "Expected {} lines but found {}. Lines must not be added or removed, nothing was renamed",
+            paths.len(),
+            names.len()
+        ));
+    }
+    let mut pairs: Vec<(PathBuf, PathBuf)> = vec![];
+    for (path, name) in paths.iter().zip(names) {
+        if name.is_empty() || name == "." || name == ".." || name.contains(is_separator) {
+            return Err(format!("\"{name}\" is not a valid name, nothing was renamed"));
+        }
+        let dest = path.with_file_name(name);
+        if dest != *path {
+            pairs.push((path.clone(), dest));
+        }
+    }
+    // Two items can't get the same name, and an item can only take the name of one being renamed
+    let mut targets: HashSet<&PathBuf> = HashSet::new();
+    let sources: HashSet<&PathBuf> = pairs.iter().map(|(src, _dest)| src).collect();
+    for (_src, dest) in &pairs {
+        if !targets.insert(dest) {
+            return Err(format!("More than one item would be named {}, nothing was renamed", dest.to_string_lossy()));
+        }
+        if dest.symlink_metadata().is_ok() && !sources.contains(dest) {
+            return Err(format!("{} already exists, nothing was renamed", dest.to_string_lossy()));
+        }
+    }
+    Ok(pairs)
+}
+
+/// Renames the selected (or marked) items by editing their names in $EDITOR, one name per line
+pub fn bulk_rename(app: &mut App, terminal: &mut DefaultTerminal) -> Result<(), String> {
+    let paths = app.explorer.selected_paths();
+    if paths.is_empty() {
+        return Ok(());
+    }
+    let names: Vec<String> = paths
+        .iter()
+        .map(|path| path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default())
+        .collect();
+    let temp_file = create_names_file(&(names.join("\n") + "\n"))?;
+
+    let edited = edit_in_terminal(app, terminal, &temp_file).and_then(|_| {
+        fs::read_to_string(&temp_file).map_err(|err| format!("Unable to read {}: {err}", temp_file.to_string_lossy()))
+    });
+    let _ = fs::remove_file(&temp_file);
+    let pairs = plan_renames(&paths, &edited?)?;
+    if pairs.is_empty() {
+        app.log_panel.set_log(String::from("No names were changed"));
+        return Ok(());
+    }
+
+    let (renamed, result) = rename_all(&pairs);
+    if !renamed.is_empty() {
+        app.undo.record(Operation::Rename(renamed.clone()));
+    }
+    app.explorer.marked.clear();
+    app.explorer.reload();
+    result?;
+    app.log_panel.set_log(format!("Renamed {} item(s)", renamed.len()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::plan_renames;
+    use crate::dependencies::tests::{read, test_dir};
+
+    #[test]
+    fn plans_a_swap_and_a_cycle() {
+        let dir = test_dir("plan-cycle", &["a", "b", "c"]);
+        let paths = [dir.join("a"), dir.join("b"), dir.join("c")];
+        assert_eq!(plan_renames(&paths[..2], "b\na\n").unwrap(), [(dir.join("a"), dir.join("b")), (dir.join("b"), dir.join("a"))]);
+        assert_eq!(
+            plan_renames(&paths, "b\nc\na").unwrap(),
+            [(dir.join("a"), dir.join("b")), (dir.join("b"), dir.join("c")), (dir.join("c"), dir.join("a"))]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn leaves_out_unchanged_names() {
+        let dir = test_dir("plan-unchanged", &["a", "b"]);
+        let paths = [dir.join("a"), dir.join("b")];
+        assert_eq!(plan_renames(&paths, "a\nc\n").unwrap(), [(dir.join("b"), dir.join("c"))]);
+        assert!(plan_renames(&paths, "a\nb\n").unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_unusable_names() {
+        let dir = test_dir("plan-refused", &["a", "b", "taken"]);
+        let paths = [dir.join("a"), dir.join("b")];
+        // A line added or removed
+        assert!(plan_renames(&paths, "a\n").is_err());
+        assert!(plan_renames(&paths, "a\nb\nc\n").is_err());
+        for edited in ["a\n\n", "a\n..\n", "a\nsub/b\n"] {
+            assert!(plan_renames(&paths, edited).is_err(), "{edited:?}");
+        }
+        // Two items with the same name
+        assert!(plan_renames(&paths, "c\nc\n").is_err());
+        // The name of an item that isn't renamed
+        assert!(plan_renames(&paths, "taken\nb\n").is_err());
+        assert_eq!(read(&dir, "taken"), "taken");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,6 +3,8 @@ use toml::de::Error;
 use ratatui::style::Color;
 use serde::Deserialize;
 
+use crate::file_colors::{FileColors, FileColorsConfig};
+
 #[derive(Debug, Default, Clone)]
 pub struct ColorTheme {
     pub primary: Color,
@@ -11,6 +13,8 @@ pub struct ColorTheme {
     pub selector: Color,
     pub explorer_bg_1: Color,
     pub explorer_bg_2: Color,
+    /// Colors of the Explorer entries by type and extension
+    pub files: FileColors,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -21,6 +25,8 @@ struct ThemeConfig {
     pub selector: String,
     pub explorer_bg_1: String,
     pub explorer_bg_2: String,
+    #[serde(default)]
+    pub file_colors: FileColorsConfig,
 }
 
 fn read_color_theme(config_path: Option<PathBuf>) -> ThemeConfig {
@@ -62,6 +68,7 @@ fn read_color_theme(config_path: Option<PathBuf>) -> ThemeConfig {
         selector: String::from("#00f0ff"),
         explorer_bg_1: String::from("#001122"),
         explorer_bg_2: String::from("#112233"),
+        file_colors: FileColorsConfig::default(),
     }
 }
 
@@ -74,5 +81,6 @@ pub fn get_color_theme(config_path: Option<PathBuf>) -> ColorTheme {
         selector: read_theme.selector.parse().unwrap_or(Color::Cyan),
         explorer_bg_1: read_theme.explorer_bg_1.parse().unwrap_or(Color::from_u32(0x00001122)),
         explorer_bg_2: read_theme.explorer_bg_2.parse().unwrap_or(Color::from_u32(0x00112233)),
+        files: FileColors::new(read_theme.file_colors),
     }
 }
\ No newline at end of file
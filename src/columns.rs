@@ -0,0 +1,325 @@
+use std::path::Path;
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Row, Table, TableState},
+};
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{
+    App,
+    color_theme::ColorTheme,
+    dependencies::HandlesInput,
+    explorer::{EntryType, FileEntry},
+    file_deps::format_time,
+    owners::{group_name, user_name},
+    permissions::mode_string,
+    settings::save_columns,
+};
+
+/// Widest a column can be made in the column picker
+const MAX_WIDTH: u16 = 200;
+
+/// A column the Explorer can show. The column of the marks is always the first one and isn't part of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnKind {
+    Type,
+    Name,
+    Extension,
+    Mode,
+    Owner,
+    Group,
+    Size,
+    Modified,
+    Created,
+    Accessed,
+    Inode,
+    Links,
+    Mime,
+}
+
+impl ColumnKind {
+    /// Shown in the header of the table
+    pub fn title(&self) -> &'static str {
+        match self {
+            ColumnKind::Type => "Type",
+            ColumnKind::Name => "Name",
+            ColumnKind::Extension => "Ext",
+            ColumnKind::Mode => "Mode",
+            ColumnKind::Owner => "Owner",
+            ColumnKind::Group => "Group",
+            ColumnKind::Size => "Size",
+            ColumnKind::Modified => "Modified At",
+            ColumnKind::Created => "Created At",
+            ColumnKind::Accessed => "Accessed At",
+            ColumnKind::Inode => "Inode",
+            ColumnKind::Links => "Links",
+            ColumnKind::Mime => "MIME Type",
+        }
+    }
+
+    /// Shown in the column picker
+    fn label(&self) -> &'static str {
+        match self {
+            ColumnKind::Type => "Type",
+            ColumnKind::Name => "Name",
+            ColumnKind::Extension => "Extension",
+            ColumnKind::Mode => "Permissions (mode)",
+            ColumnKind::Owner => "Owner",
+            ColumnKind::Group => "Group",
+            ColumnKind::Size => "Size",
+            ColumnKind::Modified => "Modified time",
+            ColumnKind::Created => "Created time",
+            ColumnKind::Accessed => "Accessed time",
+            ColumnKind::Inode => "Inode number",
+            ColumnKind::Links => "Hard link count",
+            ColumnKind::Mime => "MIME type",
+        }
+    }
+
+    // Used when no width is set for the column
+    fn default_constraint(&self) -> Constraint {
+        match self {
+            ColumnKind::Type => Constraint::Length(5),
+            ColumnKind::Name => Constraint::Min(40),
+            ColumnKind::Extension => Constraint::Length(6),
+            ColumnKind::Mode | ColumnKind::Owner | ColumnKind::Group | ColumnKind::Inode => Constraint::Length(10),
+            ColumnKind::Size => Constraint::Min(8),
+            ColumnKind::Modified => Constraint::Min(17),
+            ColumnKind::Created | ColumnKind::Accessed => Constraint::Length(17),
+            ColumnKind::Links => Constraint::Length(5),
+            ColumnKind::Mime => Constraint::Length(24),
+        }
+    }
+
+    fn default_width(&self) -> u16 {
+        match self.default_constraint() {
+            Constraint::Length(width) | Constraint::Min(width) => width,
+            _ => 10,
+        }
+    }
+}
+
+/// A column of the Explorer, as saved in `settings.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Column {
+    pub kind: ColumnKind,
+    /// Width in cells. The Name column takes this at least, and the free space on top of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u16>,
+}
+
+impl Column {
+    pub fn new(kind: ColumnKind) -> Column {
+        Column { kind, width: None }
+    }
+
+    pub fn constraint(&self) -> Constraint {
+        match (self.kind, self.width) {
+            (ColumnKind::Name, Some(width)) => Constraint::Min(width),
+            (_, Some(width)) => Constraint::Length(width),
+            (kind, None) => kind.default_constraint(),
+        }
+    }
+}
+
+/// Type, Name, Size and Modified At
+pub fn default_columns() -> Vec<Column> {
+    [ColumnKind::Type, ColumnKind::Name, ColumnKind::Size, ColumnKind::Modified].into_iter().map(Column::new).collect()
+}
+
+/// Removes the columns if the first one is shown, else adds them right after the Name column -> Used by the `M` and `O` keys
+pub fn toggle_columns(columns: &mut Vec<Column>, kinds: &[ColumnKind]) {
+    if columns.iter().any(|column| column.kind == kinds[0]) {
+        columns.retain(|column| !kinds.contains(&column.kind));
+    } else {
+        let at = columns.iter().position(|column| column.kind == ColumnKind::Name).map_or(columns.len(), |idx| idx + 1);
+        columns.splice(at..at, kinds.iter().map(|kind| Column::new(*kind)));
+    }
+}
+
+/// MIME type of an entry. The one of a file is guessed from its extension.
+pub fn mime_type(entry: &FileEntry) -> &'static str {
+    match entry.e_type {
+        EntryType::Dir => "inode/directory",
+        EntryType::Link => "inode/symlink",
+        EntryType::Fifo => "inode/fifo",
+        EntryType::Socket => "inode/socket",
+        EntryType::BlockDevice => "inode/blockdevice",
+        EntryType::CharDevice => "inode/chardevice",
+        EntryType::File => mime_guess::from_path(&entry.name).first_raw().unwrap_or("application/octet-stream"),
+    }
+}
+
+/// Text of the entry in a column. The Explorer highlights the filter match in the one of the Name column.
+pub fn cell_text(entry: &FileEntry, kind: ColumnKind) -> String {
+    match kind {
+        ColumnKind::Type => String::from(entry.glyph()),
+        ColumnKind::Name => entry.display_name(),
+        ColumnKind::Extension if entry.e_type == EntryType::Dir => String::default(),
+        ColumnKind::Extension => Path::new(&entry.name).extension().map(|ext| ext.to_string_lossy().to_string()).unwrap_or_default(),
+        ColumnKind::Mode => entry.mode.map(mode_string).unwrap_or_default(),
+        ColumnKind::Owner => entry.owner.map(|(uid, _gid)| user_name(uid)).unwrap_or_default(),
+        ColumnKind::Group => entry.owner.map(|(_uid, gid)| group_name(gid)).unwrap_or_default(),
+        ColumnKind::Size => entry.size.clone(),
+        ColumnKind::Modified => entry.modified_at.clone(),
+        ColumnKind::Created => entry.created.map(format_time).unwrap_or_default(),
+        ColumnKind::Accessed => entry.accessed.map(format_time).unwrap_or_default(),
+        ColumnKind::Inode => entry.inode.map(|inode| inode.to_string()).unwrap_or_default(),
+        ColumnKind::Links => entry.links.map(|links| links.to_string()).unwrap_or_default(),
+        ColumnKind::Mime => String::from(mime_type(entry)),
+    }
+}
+
+/// The column picker popup -> Which columns the Explorer shows, in which order and how wide
+pub struct ColumnPicker {
+    /// Every column with whether it is shown. The shown ones come first, in the order of the table.
+    rows: Vec<(Column, bool)>,
+    pub state: TableState,
+    pub color_theme: ColorTheme,
+}
+
+impl ColumnPicker {
+    pub fn new(columns: &[Column], read_color_theme: ColorTheme) -> ColumnPicker {
+        let mut rows: Vec<(Column, bool)> = columns.iter().map(|column| (*column, true)).collect();
+        for kind in ColumnKind::iter() {
+            if !columns.iter().any(|column| column.kind == kind) {
+                rows.push((Column::new(kind), false));
+            }
+        }
+        ColumnPicker {
+            rows,
+            state: TableState::default().with_selected(0),
+            color_theme: read_color_theme,
+        }
+    }
+
+    /// The columns to show, in their order
+    pub fn columns(&self) -> Vec<Column> {
+        self.rows.iter().filter(|(_column, shown)| *shown).map(|(column, _shown)| *column).collect()
+    }
+
+    /// Rows needed to show every column
+    pub fn height(&self) -> u16 {
+        self.rows.len() as u16 + 3
+    }
+
+    fn selected(&self) -> usize {
+        self.state.selected().unwrap_or(0).min(self.rows.len() - 1)
+    }
+
+    fn selected_row(&mut self) -> &mut (Column, bool) {
+        let idx = self.selected();
+        &mut self.rows[idx]
+    }
+
+    fn toggle(&mut self) {
+        let (column, shown) = self.selected_row();
+        // The table always has the names
+        if column.kind != ColumnKind::Name {
+            *shown = !*shown;
+        }
+    }
+
+    // Moves the selected column up (-1) or down (+1) in the order
+    fn move_row(&mut self, step: isize) {
+        let idx = self.selected();
+        let Some(target) = idx.checked_add_signed(step).filter(|target| *target < self.rows.len()) else {
+            return;
+        };
+        self.rows.swap(idx, target);
+        self.state.select(Some(target));
+    }
+
+    fn resize(&mut self, step: i32) {
+        let (column, _shown) = self.selected_row();
+        let width = column.width.unwrap_or(column.kind.default_width()) as i32 + step;
+        column.width = Some(width.clamp(1, MAX_WIDTH as i32) as u16);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let rows = self.rows.iter().map(|(column, shown)| {
+            let width = match column.width {
+                Some(width) => width.to_string(),
+                None => format!("{} (default)", column.kind.default_width()),
+            };
+            let style = if *shown { Style::default() } else { Style::default().fg(Color::DarkGray) };
+            Row::new([String::from(if *shown { "[x]" } else { "[ ]" }), String::from(column.kind.label()), width]).style(style)
+        });
+        let instructions = Line::from(vec![
+            " <Space>".blue().bold(),
+            " Show/Hide ".into(),
+            "<J/K>".blue().bold(),
+            " Move ".into(),
+            "<h/l>".blue().bold(),
+            " Width ".into(),
+            "<Enter>".blue().bold(),
+            " Save ".into(),
+            "<Esc>".blue().bold(),
+            " Cancel ".into(),
+        ]);
+        let table = Table::new(rows, [Constraint::Length(4), Constraint::Min(20), Constraint::Length(14)])
+            .header(Row::new(["", "Column", "Width"]).style(Style::default().fg(Color::Black).bg(self.color_theme.header)))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED).fg(self.color_theme.selector))
+            .style(Style::default().fg(self.color_theme.primary))
+            .bg(Color::Black)
+            .block(
+                Block::bordered()
+                    .border_type(ratatui::widgets::BorderType::Rounded)
+                    .title(" Columns ")
+                    .title_bottom(instructions)
+                    .border_style(Style::new().fg(self.color_theme.border)),
+            );
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
+
+impl HandlesInput for ColumnPicker {
+    fn handle_input(&mut self, event: Event) -> Result<(), String> {
+        if let Event::Key(key_event) = event && key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => self.state.select(Some((self.selected() + 1).min(self.rows.len() - 1))),
+                KeyCode::Char('k') | KeyCode::Up => self.state.select(Some(self.selected().saturating_sub(1))),
+                KeyCode::Char(' ') | KeyCode::Char('x') => self.toggle(),
+                KeyCode::Char('J') => self.move_row(1),
+                KeyCode::Char('K') => self.move_row(-1),
+                KeyCode::Char('l') | KeyCode::Right | KeyCode::Char('+') => self.resize(1),
+                KeyCode::Char('h') | KeyCode::Left | KeyCode::Char('-') => self.resize(-1),
+                KeyCode::Char('r') => self.selected_row().0.width = None,
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Opens the column picker on the columns of the Explorer
+pub fn open_column_picker(app: &mut App) {
+    app.column_picker = Some(ColumnPicker::new(&app.explorer.columns, app.explorer.color_theme.clone()));
+}
+
+/// Gives the columns of the picker to every Explorer (panes and tabs), saves them in `settings.toml` and closes the picker
+pub fn apply_columns(app: &mut App) {
+    let Some(picker) = app.column_picker.take() else {
+        return;
+    };
+    let columns = picker.columns();
+    app.explorer.columns = columns.clone();
+    if let Some(other) = app.other_pane.as_mut() {
+        other.explorer.columns = columns.clone();
+    }
+    for tab in app.tabs.iter_mut() {
+        tab.explorer.columns = columns.clone();
+    }
+    match save_columns(&app.settings_path, &columns) {
+        Ok(()) => app.log_panel.set_log(format!("Saved the columns in {}", app.settings_path.to_string_lossy())),
+        Err(err) => app.log_panel.set_log(err),
+    }
+}
@@ -1,10 +1,10 @@
-use std::{fs::{self, File, create_dir_all}, path::PathBuf};
+use std::{fs::{File, create_dir_all}, path::PathBuf};
 
 use crossterm::event::{Event, KeyCode, KeyEventKind};
 use ratatui::{Frame, layout::Rect, style::{Style, Stylize}, text::{Line}, widgets::{Block, Paragraph}};
-use tui_input::{Input, InputRequest, backend::crossterm::EventHandler};
+use tui_input::{Input, backend::crossterm::EventHandler};
 
-use crate::{App, CurrentWidget, color_theme::ColorTheme, dependencies::{HandlesInput, InputMode, focus_to}};
+use crate::{App, CurrentWidget, batch_rename::apply_batch_rename, color_theme::ColorTheme, conflict::{OperationKind, start_operation}, links::ln_command, owners::chown_command, permissions::created_mode_note, undo::Operation, dependencies::{HandlesInput, InputMode, focus_to}, sort::SortKey};
 
 pub struct Command {
     /// Current value of the input box
@@ -12,6 +12,8 @@ pub struct Command {
     /// Current input mode
     pub input_mode: InputMode,
     pub in_focus: bool,
+    /// Where the input box was last drawn -> Used to focus it on a click
+    pub area: Rect,
     pub color_theme: ColorTheme,
 }
 
@@ -21,18 +23,18 @@ impl Command {
             input: Input::new(String::from("")),
             input_mode: InputMode::Normal,
             in_focus: false,
+            area: Rect::default(),
             color_theme: read_color_theme,
         }
     }
 
-    pub fn set_value(&mut self, value: String) {
-        self.input.reset();
-        for c in value.chars() {
-            self.input.handle(InputRequest::InsertChar(c));
-        }
+    /// Text typed in the Command bar
+    pub fn value(&self) -> &str {
+        self.input.value()
     }
 
-    pub fn render_input(&self, frame: &mut Frame, area: Rect) {
+    pub fn render_input(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
         // keep 2 for borders and 1 for cursor
         let width = area.width.max(3) - 3;
         let scroll = self.input.visual_scroll(width as usize);
@@ -83,10 +85,11 @@ pub fn handle_command_enter(app: &mut App) {
             let dir_path = PathBuf::from(app.path_field.input.value());
             let mut new_file = dir_path.clone();
             new_file.push(split_cmd[1]);
-            if let Err(_err) = File::create_new(new_file) {
-                app.command.set_value(String::from("The file already exists"));
+            if let Err(_err) = File::create_new(&new_file) {
+                app.log_panel.set_log(String::from("The file already exists"));
             } else {
-                app.command.set_value(String::from("The file was created successfully"));
+                app.log_panel.set_log(format!("The file was created successfully{}", created_mode_note(&new_file)));
+                app.undo.record(Operation::Create { path: new_file, is_dir: false });
             }
             app.explorer.refresh(&dir_path, app.include_hidden);
         },
@@ -94,10 +97,14 @@ pub fn handle_command_enter(app: &mut App) {
             let dir_path = PathBuf::from(app.path_field.input.value());
             let mut new_file = dir_path.clone();
             new_file.push(split_cmd[1]);
-            if let Err(_err) = create_dir_all(new_file) {
-                app.command.set_value(String::from("The Directory already exists"));
+            let already_exists = new_file.exists();
+            if let Err(_err) = create_dir_all(&new_file) {
+                app.log_panel.set_log(String::from("The Directory already exists"));
             } else {
-                app.command.set_value(String::from("The Directory was created successfully"));
+                app.log_panel.set_log(format!("The Directory was created successfully{}", created_mode_note(&new_file)));
+                if !already_exists {
+                    app.undo.record(Operation::Create { path: new_file, is_dir: true });
+                }
             }
             app.explorer.refresh(&dir_path, app.include_hidden);
         },
@@ -106,11 +113,82 @@ pub fn handle_command_enter(app: &mut App) {
             let src = root.join(split_cmd[1]);
             let dest = root.join(split_cmd[2]);
             if src.exists() {
-                if let Err(_err) = fs::rename(src, dest) {app.log_panel.set_log(String::from("Rename operation failed"));};
+                start_operation(app, OperationKind::Rename, vec![(src, dest)]);
             } else {
                 app.log_panel.set_log(String::from("File to rename not found"));
             }
-            app.explorer.refresh(&root, app.include_hidden);
+        },
+        "rename" => {
+            let args = cmd.strip_prefix("rename").unwrap_or_default().to_string();
+            if let Err(err) = apply_batch_rename(app, &args) {
+                app.log_panel.set_log(err);
+            }
+        },
+        "sort" => {
+            let mut sort = app.explorer.sort;
+            let mut valid_args = true;
+            for arg in split_cmd.iter().skip(1).filter(|arg| !arg.is_empty()) {
+                match *arg {
+                    "asc" => sort.descending = false,
+                    "desc" => sort.descending = true,
+                    "dirs" => sort.dirs_first = !sort.dirs_first,
+                    _ => match SortKey::from_arg(arg) {
+                        Some(key) => sort.key = key,
+                        None => {
+                            app.log_panel.set_log(format!("Unknown sort option: {arg}"));
+                            valid_args = false;
+                        },
+                    },
+                }
+            }
+            if valid_args {
+                app.explorer.set_sort(sort);
+                app.log_panel.set_log(format!(
+                    "Sorted by {} ({}){}",
+                    sort.key.label(),
+                    if sort.descending { "descending" } else { "ascending" },
+                    if sort.dirs_first { ", directories first" } else { "" },
+                ));
+            }
+        },
+        "grep" => {
+            let ignore_case = split_cmd.get(1) == Some(&"-i");
+            let pattern_start = if ignore_case { 2 } else { 1 };
+            let pattern = split_cmd.get(pattern_start..).map(|words| words.join(" ")).unwrap_or_default();
+            if pattern.is_empty() {
+                app.log_panel.set_log(String::from("Usage: grep [-i] <PATTERN>"));
+            } else {
+                let root = PathBuf::from(app.path_field.input.value());
+                match app.grep.start(&pattern, ignore_case, root, app.include_hidden, app.event_tx.clone()) {
+                    Ok(()) => app.grep_shown = true,
+                    Err(err) => app.log_panel.set_log(err),
+                }
+            }
+        },
+        "chown" => {
+            let args = cmd.strip_prefix("chown").unwrap_or_default().to_string();
+            if let Err(err) = chown_command(app, &args) {
+                app.log_panel.set_log(err);
+            }
+        },
+        "ln" => {
+            let args = cmd.strip_prefix("ln").unwrap_or_default().to_string();
+            if let Err(err) = ln_command(app, &args) {
+                app.log_panel.set_log(err);
+            }
+        },
+        "du" => {
+            let cross_fs = split_cmd.get(1) == Some(&"--cross-fs");
+            let path_start = if cross_fs { 2 } else { 1 };
+            let root = PathBuf::from(app.path_field.input.value());
+            let root = match split_cmd.get(path_start..).map(|words| words.join(" ")).filter(|path| !path.is_empty()) {
+                Some(path) => root.join(path),
+                None => root,
+            };
+            match app.disk_usage.start(root, cross_fs, app.event_tx.clone()) {
+                Ok(()) => app.disk_usage_shown = true,
+                Err(err) => app.log_panel.set_log(err),
+            }
         },
         "term" => {
             if cfg!(target_os = "windows") {
@@ -0,0 +1,333 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{App, color_theme::ColorTheme, dependencies::remove_path, file_size_deps::{convert, find_bytes}, file_deps::format_time, jobs::{JobItem, JobKind, JobOrigin}, undo::Operation};
+
+/// What to do when the destination of a paste, move or rename already exists
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Prompt for every conflict
+    #[default]
+    Ask,
+    Overwrite,
+    Skip,
+    /// Keep both, the new one is named `name (2).ext`
+    Rename,
+    /// Overwrite only when the source was modified after the destination
+    OverwriteIfNewer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Copy,
+    Move,
+    Rename,
+}
+
+impl OperationKind {
+    fn label(&self) -> &'static str {
+        match self {
+            OperationKind::Copy => "Copy",
+            OperationKind::Move => "Move",
+            OperationKind::Rename => "Rename",
+        }
+    }
+}
+
+/// A paste, move or rename waiting for its conflicts to be resolved
+pub struct PendingOperation {
+    pub kind: OperationKind,
+    /// (source, destination) pairs as asked for
+    items: Vec<(PathBuf, PathBuf)>,
+    /// Items to carry out, once the conflicts are resolved
+    resolved: Vec<JobItem>,
+    /// Index of the item being resolved
+    next: usize,
+    /// Policy chosen with "apply to all" in the prompt
+    apply_to_all: Option<ConflictPolicy>,
+    skipped: Vec<String>,
+    /// A paste of a cut clipboard -> The clipboard is emptied once the move is queued
+    from_cut_clipboard: bool,
+    pub color_theme: ColorTheme,
+}
+
+/// `name (2).ext`, with the first number that is free in the directory and among the `taken` paths
+pub fn numbered_name(dest: &Path, taken: &[JobItem]) -> PathBuf {
+    let parent = dest.parent().unwrap_or(Path::new("."));
+    let stem = dest.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = dest.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    let mut n = 2;
+    loop {
+        let candidate = parent.join(format!("{stem} ({n}){ext}"));
+        if !exists(&candidate) && !taken.iter().any(|item| item.dest == candidate) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+// Renaming to the same name, or only changing its case on a case insensitive file system, is not a conflict
+#[cfg(unix)]
+fn same_entry(src: &Path, dest: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (src.symlink_metadata(), dest.symlink_metadata()) {
+        (Ok(src_meta), Ok(dest_meta)) => src_meta.dev() == dest_meta.dev() && src_meta.ino() == dest_meta.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_entry(src: &Path, dest: &Path) -> bool {
+    src.to_string_lossy().to_lowercase() == dest.to_string_lossy().to_lowercase()
+}
+
+// Broken symlinks exist too
+fn exists(path: &Path) -> bool {
+    path.symlink_metadata().is_ok()
+}
+
+fn is_newer(src: &Path, dest: &Path) -> bool {
+    let modified = |path: &Path| path.symlink_metadata().and_then(|meta| meta.modified()).ok();
+    match (modified(src), modified(dest)) {
+        (Some(src_time), Some(dest_time)) => src_time > dest_time,
+        _ => false,
+    }
+}
+
+impl PendingOperation {
+    pub fn new(kind: OperationKind, items: Vec<(PathBuf, PathBuf)>, read_color_theme: ColorTheme) -> PendingOperation {
+        PendingOperation {
+            kind,
+            items,
+            resolved: vec![],
+            next: 0,
+            apply_to_all: None,
+            skipped: vec![],
+            from_cut_clipboard: false,
+            color_theme: read_color_theme,
+        }
+    }
+
+    /// The item the prompt is shown for
+    fn current(&self) -> Option<&(PathBuf, PathBuf)> {
+        self.items.get(self.next)
+    }
+
+    fn in_conflict(&self, src: &Path, dest: &Path) -> bool {
+        if self.kind == OperationKind::Rename && same_entry(src, dest) {
+            return false;
+        }
+        exists(dest) || self.resolved.iter().any(|item| item.dest == dest)
+    }
+
+    // Settles the current item with `policy`
+    fn settle(&mut self, policy: ConflictPolicy) {
+        let Some((src, dest)) = self.current().cloned() else {
+            return;
+        };
+        let name = dest.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        match policy {
+            ConflictPolicy::Skip | ConflictPolicy::Ask => self.skipped.push(name),
+            ConflictPolicy::Rename => {
+                let renamed = numbered_name(&dest, &self.resolved);
+                self.resolved.push(JobItem::new(src, renamed));
+            },
+            ConflictPolicy::Overwrite | ConflictPolicy::OverwriteIfNewer => {
+                // The source itself (or the directory holding it) would be deleted
+                if src == dest || src.starts_with(&dest) {
+                    self.skipped.push(format!("{name} (can't be overwritten with itself)"));
+                } else if policy == ConflictPolicy::OverwriteIfNewer && !is_newer(&src, &dest) {
+                    self.skipped.push(name);
+                } else {
+                    self.resolved.push(JobItem { overwrite: true, ..JobItem::new(src, dest) });
+                }
+            },
+        }
+        self.next += 1;
+    }
+
+    /// Goes through the items until one needs to be asked about. Returns true when all of them are resolved.
+    pub fn resolve(&mut self, default_policy: ConflictPolicy) -> bool {
+        while let Some((src, dest)) = self.current().cloned() {
+            if !self.in_conflict(&src, &dest) {
+                self.resolved.push(JobItem::new(src, dest));
+                self.next += 1;
+                continue;
+            }
+            match self.apply_to_all.unwrap_or(default_policy) {
+                ConflictPolicy::Ask => return false,
+                policy => self.settle(policy),
+            }
+        }
+        true
+    }
+
+    /// Handles a key of the prompt. Returns false if the key isn't one of the choices.
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> bool {
+        let (policy, to_all) = match key_event.code {
+            KeyCode::Char('o') => (ConflictPolicy::Overwrite, false),
+            KeyCode::Char('s') => (ConflictPolicy::Skip, false),
+            KeyCode::Char('r') => (ConflictPolicy::Rename, false),
+            KeyCode::Char('n') => (ConflictPolicy::OverwriteIfNewer, false),
+            KeyCode::Char('O') => (ConflictPolicy::Overwrite, true),
+            KeyCode::Char('S') => (ConflictPolicy::Skip, true),
+            KeyCode::Char('R') => (ConflictPolicy::Rename, true),
+            KeyCode::Char('N') => (ConflictPolicy::OverwriteIfNewer, true),
+            _ => return false,
+        };
+        if to_all {
+            self.apply_to_all = Some(policy);
+        }
+        self.settle(policy);
+        true
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let Some((src, dest)) = self.current() else {
+            return;
+        };
+        frame.render_widget(Clear, area);
+        let describe = |path: &Path| match path.symlink_metadata() {
+            Ok(meta) => format!(
+                "{}, modified {}",
+                if meta.is_dir() { String::from("directory") } else { convert(find_bytes(path, false) as f64) },
+                meta.modified().map(format_time).unwrap_or_default()
+            ),
+            Err(_) => String::from("(being added by this operation)"),
+        };
+        let name = dest.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let dir = dest.parent().map(|dir| dir.to_string_lossy().to_string()).unwrap_or_default();
+        let text = vec![
+            Line::from(format!(" \"{name}\" already exists in {dir}")),
+            Line::from(""),
+            Line::from(format!(" New:      {}", describe(src))),
+            Line::from(format!(" Existing: {}", describe(dest))),
+            Line::from(""),
+            Line::from(vec![
+                " <o>".blue().bold(), " Overwrite ".into(),
+                "<s>".blue().bold(), " Skip ".into(),
+                "<r>".blue().bold(), " Keep both ".into(),
+                "<n>".blue().bold(), " Overwrite if newer ".into(),
+            ]),
+            Line::from(" Upper case (O / S / R / N) applies the choice to all the remaining conflicts. <Esc> cancels."),
+        ];
+        let prompt = Paragraph::new(text)
+            .style(Style::default().fg(self.color_theme.primary))
+            .bg(Color::Black)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::bordered()
+                    .border_type(ratatui::widgets::BorderType::Rounded)
+                    .title(format!(" {}: {} of {} ", self.kind.label(), self.next + 1, self.items.len()))
+                    .border_style(Style::new().fg(self.color_theme.border)),
+            );
+        frame.render_widget(prompt, area);
+    }
+}
+
+// Renames right away. Only a destination chosen to be overwritten is removed, the others are never replaced.
+// Returns the pairs that were renamed.
+fn rename_items(items: &[JobItem]) -> (Vec<(PathBuf, PathBuf)>, Result<(), String>) {
+    let mut renamed: Vec<(PathBuf, PathBuf)> = vec![];
+    let mut failed: Vec<String> = vec![];
+    for JobItem { src, dest, overwrite, .. } in items {
+        let result = if !exists(dest) || same_entry(src, dest) {
+            fs::rename(src, dest)
+        } else if *overwrite {
+            remove_path(dest).and_then(|_| fs::rename(src, dest))
+        } else {
+            Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, "already exists"))
+        };
+        match result {
+            Ok(()) => renamed.push((src.clone(), dest.clone())),
+            Err(err) => failed.push(format!("{}: {err}", src.to_string_lossy())),
+        }
+    }
+    if !failed.is_empty() {
+        return (renamed, Err(format!("Rename operation failed: {}", failed.join(", "))));
+    }
+    (renamed, Ok(()))
+}
+
+/// Starts a copy, move or rename. A prompt is shown for the conflicts the configured policy leaves to the user.
+pub fn start_operation(app: &mut App, kind: OperationKind, items: Vec<(PathBuf, PathBuf)>) {
+    if items.is_empty() {
+        return;
+    }
+    app.pending_operation = Some(PendingOperation::new(kind, items, app.explorer.color_theme.clone()));
+    continue_operation(app);
+}
+
+/// Pastes the clipboard into the directory of the Explorer. A cut clipboard keeps its items until the move is
+/// queued, so that cancelling the conflict prompt doesn't lose them.
+pub fn paste_clipboard(app: &mut App) {
+    let (kind, items) = app.clipboard.paste_items(&app.explorer.root_path);
+    if items.is_empty() {
+        return;
+    }
+    let mut operation = PendingOperation::new(kind, items, app.explorer.color_theme.clone());
+    operation.from_cut_clipboard = app.clipboard.is_cut;
+    app.pending_operation = Some(operation);
+    continue_operation(app);
+}
+
+/// Carries on with the pending operation after a choice was made in the prompt
+pub fn continue_operation(app: &mut App) {
+    let Some(operation) = app.pending_operation.as_mut() else {
+        return;
+    };
+    if !operation.resolve(app.conflict_policy) {
+        return;
+    }
+    let Some(operation) = app.pending_operation.take() else {
+        return;
+    };
+    if !operation.skipped.is_empty() {
+        app.log_panel.set_log(format!("Skipped: {}", operation.skipped.join(", ")));
+    }
+    match operation.kind {
+        OperationKind::Copy => app.jobs.enqueue(JobKind::Copy, operation.resolved, JobOrigin::User),
+        OperationKind::Move => {
+            if operation.from_cut_clipboard && !operation.resolved.is_empty() {
+                app.clipboard.clear_cut();
+            }
+            app.jobs.enqueue(JobKind::Move, operation.resolved, JobOrigin::User);
+        },
+        OperationKind::Rename => {
+            let (renamed, result) = rename_items(&operation.resolved);
+            if !renamed.is_empty() {
+                app.undo.record(Operation::Rename(renamed));
+            }
+            if let Err(err) = result {
+                app.log_panel.set_log(err);
+            }
+            app.explorer.reload();
+        },
+    }
+}
+
+/// Keys of the conflict prompt
+pub fn conflict_handle_key(app: &mut App, key_event: KeyEvent) {
+    if key_event.code == KeyCode::Esc {
+        app.pending_operation = None;
+        app.log_panel.set_log(String::from("Operation cancelled"));
+        return;
+    }
+    if let Some(operation) = app.pending_operation.as_mut() && operation.handle_key(key_event) {
+        continue_operation(app);
+    }
+}
@@ -1,7 +1,8 @@
-use std::{fs::{self, remove_dir_all, remove_file}, path::PathBuf};
+use std::{fs, io, path::{Path, PathBuf}};
 
-use fs_extra::dir::{CopyOptions, copy};
-use crate::{App, CurrentWidget, explorer::EntryType};
+use ratatui::layout::{Margin, Position, Rect};
+
+use crate::{App, CurrentWidget};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
@@ -40,31 +41,165 @@ pub trait HandlesInput {
     fn handle_input(&mut self, event: crossterm::event::Event) -> Result<(), String>;
 }
 
-pub fn copy_file(src: &PathBuf, dest: &PathBuf) -> Result<u64, String> {
-    if let Ok(_success) = fs::copy(src, dest) {
-        Ok(_success)
-    } else {
-        Err(format!(
-            "Error in copying file {}",
-            src.to_str().unwrap()
-        ))
+/// Index of the row at `position` of a bordered table with a header, drawn in `area` and scrolled by `offset` rows
+pub fn table_row_at(area: Rect, offset: usize, position: Position) -> Option<usize> {
+    // The border and the header come before the first row
+    let first_row_y = area.y + 2;
+    let inner = area.inner(Margin { vertical: 1, horizontal: 1 });
+    if !inner.contains(position) || position.y < first_row_y {
+        return None;
     }
+    Some(offset + (position.y - first_row_y) as usize)
+}
+
+/// Files with a NUL byte in their first block are treated as binary
+pub fn is_binary(head: &[u8]) -> bool {
+    head.contains(&0)
 }
 
-pub fn copy_directory(src: &PathBuf, dest: &PathBuf) -> Result<(), String> {
-    let options = CopyOptions::new().copy_inside(true);
-    if let Ok(_copied_size) = copy(src, dest, &options) {
-        Ok(())
+/// Deletes a file, a symlink or a whole directory tree
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if path.symlink_metadata()?.is_dir() {
+        fs::remove_dir_all(path)
     } else {
-        Err(String::from("The Folder was not copied successfully."))
+        fs::remove_file(path)
     }
 }
 
-pub fn delete(file_path: &PathBuf, file_type: EntryType) -> Result<(), String> {
-    if file_type == EntryType::Dir {
-        if let Err(err) = remove_dir_all(&file_path) {return Err(format!("Directory could not be deleted: {err}"))};
-    } else if file_type == EntryType::File {
-        if let Err(err) = remove_file(file_path) {return Err(format!("File could not be deleted: {err}"))};
+// A temporary name next to `src` that nothing has. A name left by an earlier batch that failed is skipped, not overwritten.
+fn free_temp_path(src: &Path, idx: usize) -> PathBuf {
+    let base = format!(".columbus-rename-{}-{idx}", std::process::id());
+    let mut temp_path = src.with_file_name(&base);
+    let mut attempt = 1;
+    while temp_path.symlink_metadata().is_ok() {
+        temp_path = src.with_file_name(format!("{base}-{attempt}"));
+        attempt += 1;
+    }
+    temp_path
+}
+
+/// Renames all the (source, destination) pairs in two steps, through temporary names in the same directory.
+/// This way swaps and cycles (a -> b, b -> a) work. A destination taken by something else is never overwritten, the
+/// item is put back under its old name instead. Returns the pairs that were renamed.
+pub fn rename_all(pairs: &[(PathBuf, PathBuf)]) -> (Vec<(PathBuf, PathBuf)>, Result<(), String>) {
+    let mut failed: Vec<String> = vec![];
+    let mut staged: Vec<(PathBuf, PathBuf, PathBuf)> = vec![];
+    for (idx, (src, dest)) in pairs.iter().enumerate() {
+        let temp_path = free_temp_path(src, idx);
+        match fs::rename(src, &temp_path) {
+            Ok(()) => staged.push((src.clone(), temp_path, dest.clone())),
+            Err(err) => failed.push(format!("{}: {err}", src.to_string_lossy())),
+        }
+    }
+    let mut renamed: Vec<(PathBuf, PathBuf)> = vec![];
+    for (src, temp_path, dest) in staged {
+        // The sources are all out of the way by now -> Anything at the destination appeared since the renames were planned
+        let result = if dest.symlink_metadata().is_ok() {
+            Err(io::Error::from(io::ErrorKind::AlreadyExists))
+        } else {
+            fs::rename(&temp_path, &dest)
+        };
+        match result {
+            Ok(()) => renamed.push((src, dest)),
+            Err(err) => {
+                // Putting it back under its old name, unless that was taken by another item meanwhile
+                if src.symlink_metadata().is_err() && fs::rename(&temp_path, &src).is_ok() {
+                    failed.push(format!("{}: {err}", src.to_string_lossy()));
+                } else {
+                    failed.push(format!("{}: {err} (left as {})", src.to_string_lossy(), temp_path.to_string_lossy()));
+                }
+            },
+        }
+    }
+    if !failed.is_empty() {
+        return (renamed, Err(format!("Rename operation failed: {}", failed.join(", "))));
+    }
+    (renamed, Ok(()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{env, fs, path::{Path, PathBuf}, process};
+
+    use super::rename_all;
+
+    /// An empty directory of its own for the test, with a file for each of `files` holding its name
+    pub(crate) fn test_dir(name: &str, files: &[&str]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("columbus-test-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for file in files {
+            fs::write(dir.join(file), file).unwrap();
+        }
+        dir
+    }
+
+    /// The contents of the file -> The name it had when the test started
+    pub(crate) fn read(dir: &Path, name: &str) -> String {
+        fs::read_to_string(dir.join(name)).unwrap()
+    }
+
+    // Nothing is left under a temporary name
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn swaps_two_names() {
+        let dir = test_dir("swap", &["a", "b"]);
+        let pairs = vec![(dir.join("a"), dir.join("b")), (dir.join("b"), dir.join("a"))];
+        let (renamed, result) = rename_all(&pairs);
+        assert!(result.is_ok());
+        assert_eq!(renamed, pairs);
+        assert_eq!(read(&dir, "a"), "b");
+        assert_eq!(read(&dir, "b"), "a");
+        assert_eq!(entries(&dir), ["a", "b"]);
+        fs::remove_dir_all(&dir).unwrap();
     }
-    Ok(())
-}
\ No newline at end of file
+
+    #[test]
+    fn renames_a_cycle() {
+        let dir = test_dir("cycle", &["a", "b", "c"]);
+        let pairs = vec![(dir.join("a"), dir.join("b")), (dir.join("b"), dir.join("c")), (dir.join("c"), dir.join("a"))];
+        let (renamed, result) = rename_all(&pairs);
+        assert!(result.is_ok());
+        assert_eq!(renamed.len(), 3);
+        assert_eq!(read(&dir, "b"), "a");
+        assert_eq!(read(&dir, "c"), "b");
+        assert_eq!(read(&dir, "a"), "c");
+        assert_eq!(entries(&dir), ["a", "b", "c"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_an_entry_at_the_temporary_name() {
+        // Left by an earlier batch that failed
+        let leftover = format!(".columbus-rename-{}-0", process::id());
+        let dir = test_dir("leftover", &["a", &leftover]);
+        let (renamed, result) = rename_all(&[(dir.join("a"), dir.join("b"))]);
+        assert!(result.is_ok());
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(read(&dir, "b"), "a");
+        assert_eq!(read(&dir, &leftover), leftover);
+        assert_eq!(entries(&dir), [leftover.as_str(), "b"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn puts_back_an_item_whose_target_appeared() {
+        let dir = test_dir("rollback", &["a", "b"]);
+        // Planned while "c" was free, created by someone else before the renames ran
+        let pairs = vec![(dir.join("a"), dir.join("c")), (dir.join("b"), dir.join("d"))];
+        fs::write(dir.join("c"), "c").unwrap();
+        let (renamed, result) = rename_all(&pairs);
+        assert!(result.is_err());
+        assert_eq!(renamed, [(dir.join("b"), dir.join("d"))]);
+        assert_eq!(read(&dir, "a"), "a");
+        assert_eq!(read(&dir, "c"), "c");
+        assert_eq!(read(&dir, "d"), "b");
+        assert_eq!(entries(&dir), ["a", "c", "d"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Sender},
+    },
+    thread,
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AppEvent, explorer::{EntryType, Explorer, FileEntry}, file_size_deps::convert, sort::SortKey};
+
+/// What the Size column shows for directories
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirSizeMode {
+    /// `...`, nothing is computed
+    #[default]
+    Off,
+    /// Total size of the files under the directory
+    Size,
+    /// Number of entries directly in the directory
+    Count,
+}
+
+impl DirSizeMode {
+    /// To cycle through the modes -> Used to handle the `z` Keyevent
+    pub fn next(&self) -> Self {
+        match self {
+            DirSizeMode::Off => DirSizeMode::Size,
+            DirSizeMode::Size => DirSizeMode::Count,
+            DirSizeMode::Count => DirSizeMode::Off,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DirSizeMode::Off => "off",
+            DirSizeMode::Size => "total size",
+            DirSizeMode::Count => "item count",
+        }
+    }
+}
+
+/// What was found in a directory. `bytes` is only computed in the `Size` mode as it walks the whole tree.
+#[derive(Debug, Clone, Copy)]
+pub struct DirSummary {
+    pub items: u64,
+    pub bytes: Option<u64>,
+}
+
+impl DirSummary {
+    fn text(&self, mode: DirSizeMode) -> Option<String> {
+        match mode {
+            DirSizeMode::Off => None,
+            DirSizeMode::Size => self.bytes.map(|bytes| convert(bytes as f64)),
+            DirSizeMode::Count if self.items == 1 => Some(String::from("1 item")),
+            DirSizeMode::Count => Some(format!("{} items", self.items)),
+        }
+    }
+}
+
+struct DirSizeRequest {
+    generation: u64,
+    mode: DirSizeMode,
+    /// Directories with the modification time they were listed with
+    dirs: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+/// Computes the sizes of the directories listed in the Explorers on a worker thread and fills them in as they arrive
+pub struct DirSizes {
+    pub mode: DirSizeMode,
+    /// (path, modification time) -> summary. A directory is computed again once its modification time changes.
+    cache: HashMap<PathBuf, (Option<SystemTime>, DirSummary)>,
+    /// Directories asked for by the latest request that haven't come back yet
+    pending: HashSet<PathBuf>,
+    /// Bumped on every request. The worker drops a request as soon as a newer one is made.
+    generation: Arc<AtomicU64>,
+    request_tx: Sender<DirSizeRequest>,
+}
+
+// Entries directly in the directory
+fn count_items(path: &Path) -> u64 {
+    fs::read_dir(path).map(|read_dir| read_dir.count() as u64).unwrap_or(0)
+}
+
+// Total size of the files under `root`, without following symlinks. `None` when a newer request was made meanwhile.
+fn total_size(root: &Path, generation: u64, latest: &AtomicU64) -> Option<u64> {
+    let mut total: u64 = 0;
+    let mut stack: Vec<PathBuf> = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if latest.load(Ordering::Relaxed) != generation {
+            return None;
+        }
+        let Ok(read_dir) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            match entry.metadata() {
+                Ok(meta) if meta.is_dir() => stack.push(entry.path()),
+                Ok(meta) => total += meta.len(),
+                Err(_) => {},
+            }
+        }
+    }
+    Some(total)
+}
+
+// Runs on the directory size thread. Only the latest request is served when several are waiting.
+fn dir_size_worker(request_rx: mpsc::Receiver<DirSizeRequest>, event_tx: Sender<AppEvent>, latest: Arc<AtomicU64>) {
+    while let Ok(mut request) = request_rx.recv() {
+        while let Ok(newer) = request_rx.try_recv() {
+            request = newer;
+        }
+        // The counts are quick, they are all sent before the first size
+        let mut summaries: Vec<(PathBuf, Option<SystemTime>, DirSummary)> = request
+            .dirs
+            .into_iter()
+            .map(|(path, modified)| {
+                let items = count_items(&path);
+                (path, modified, DirSummary { items, bytes: None })
+            })
+            .collect();
+        if request.mode == DirSizeMode::Count {
+            for (path, modified, summary) in summaries {
+                if event_tx.send(AppEvent::DirSize(request.generation, path, modified, summary)).is_err() {
+                    return;
+                }
+            }
+        } else {
+            for (path, modified, summary) in summaries.iter_mut() {
+                let Some(bytes) = total_size(path, request.generation, &latest) else {
+                    break;
+                };
+                summary.bytes = Some(bytes);
+                if event_tx.send(AppEvent::DirSize(request.generation, path.clone(), *modified, *summary)).is_err() {
+                    return;
+                }
+            }
+        }
+        if event_tx.send(AppEvent::DirSizesDone(request.generation)).is_err() {
+            return;
+        }
+    }
+}
+
+impl DirSizes {
+    pub fn new(mode: DirSizeMode, event_tx: Sender<AppEvent>) -> DirSizes {
+        let (request_tx, request_rx) = mpsc::channel::<DirSizeRequest>();
+        let generation = Arc::new(AtomicU64::new(0));
+        let latest = Arc::clone(&generation);
+        thread::spawn(move || dir_size_worker(request_rx, event_tx, latest));
+        DirSizes {
+            mode,
+            cache: HashMap::new(),
+            pending: HashSet::new(),
+            generation,
+            request_tx,
+        }
+    }
+
+    fn cached(&self, path: &Path, modified: Option<SystemTime>) -> Option<DirSummary> {
+        self.cache
+            .get(path)
+            .filter(|(cached_modified, summary)| *cached_modified == modified && (self.mode != DirSizeMode::Size || summary.bytes.is_some()))
+            .map(|(_modified, summary)| *summary)
+    }
+
+    /// Fills in the known sizes of the directories listed in the Explorers, and asks the worker for the others
+    pub fn request(&mut self, explorers: &mut [&mut Explorer]) {
+        if self.mode == DirSizeMode::Off {
+            return;
+        }
+        let mut missing: Vec<(PathBuf, Option<SystemTime>)> = vec![];
+        let mut seen: HashSet<PathBuf> = HashSet::new();
+        for explorer in explorers.iter_mut() {
+            let root_path = explorer.root_path.clone();
+            for entry in explorer.unfiltered.iter_mut().chain(explorer.files.iter_mut()) {
+                if entry.e_type != EntryType::Dir {
+                    continue;
+                }
+                let path = root_path.join(&entry.name);
+                match self.cached(&path, entry.modified) {
+                    Some(summary) => fill(entry, &summary, self.mode),
+                    None => {
+                        if seen.insert(path.clone()) {
+                            missing.push((path, entry.modified));
+                        }
+                    },
+                }
+            }
+        }
+        if missing.iter().all(|(path, _modified)| self.pending.contains(path)) {
+            return;
+        }
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        self.pending = missing.iter().map(|(path, _modified)| path.clone()).collect();
+        let _ = self.request_tx.send(DirSizeRequest { generation, mode: self.mode, dirs: missing });
+    }
+
+    /// Keeps a result of the worker. It is filled in by the next `request`.
+    pub fn set_result(&mut self, generation: u64, path: PathBuf, modified: Option<SystemTime>, summary: DirSummary) {
+        if generation == self.generation.load(Ordering::Relaxed) && (self.mode == DirSizeMode::Count || summary.bytes.is_some()) {
+            self.pending.remove(&path);
+        }
+        self.cache.insert(path, (modified, summary));
+    }
+
+    /// Whether the generation is the one of the latest request
+    pub fn is_latest(&self, generation: u64) -> bool {
+        generation == self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Switches to the next mode. The Explorers need a reload to drop the sizes shown.
+    pub fn toggle(&mut self) {
+        self.mode = self.mode.next();
+        self.pending.clear();
+        // Stops the work on the current request
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Shows the summary in the Size column of the directory. The bytes are also used when sorting by size.
+fn fill(entry: &mut FileEntry, summary: &DirSummary, mode: DirSizeMode) {
+    if let Some(text) = summary.text(mode) && entry.size != text {
+        entry.size = text;
+        entry.size_bytes = summary.bytes.unwrap_or(0);
+    }
+}
+
+/// Sorts the Explorer again once the sizes of its directories are all in, when it is sorted by size
+pub fn resort_by_size(explorer: &mut Explorer) {
+    if explorer.sort.key == SortKey::Size {
+        explorer.set_sort(explorer.sort);
+    }
+}
@@ -0,0 +1,486 @@
+use std::{
+    collections::HashSet,
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+    sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, Cell, Clear, HighlightSpacing, Paragraph, Row, Table, TableState},
+};
+
+use crate::{App, AppEvent, color_theme::ColorTheme, dependencies::HandlesInput, file_size_deps::convert, undo::Operation};
+
+/// Progress is sent at most this often while scanning
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// Width of the percentage bars
+const BAR_WIDTH: usize = 10;
+/// Deep trees are walked recursively, the scan thread gets a larger stack than the default
+const SCAN_STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// An entry of the scanned tree with the totals of everything under it
+#[derive(Debug, Clone)]
+pub struct DuNode {
+    pub name: String,
+    pub is_dir: bool,
+    /// Sum of the file lengths
+    pub apparent: u64,
+    /// Space taken on the disk (allocated blocks)
+    pub disk: u64,
+    /// Files under it, or 1 for a file
+    pub files: u64,
+    pub children: Vec<DuNode>,
+    /// Why the entry wasn't scanned or counted, if it wasn't
+    pub note: Option<String>,
+}
+
+pub struct DuScan {
+    pub root_path: PathBuf,
+    pub root: DuNode,
+    /// Directories that couldn't be read, with the error
+    pub unreadable: Vec<String>,
+}
+
+/// The disk usage view -> Scans a tree once and browses it sorted by size
+pub struct DiskUsage {
+    pub scan: Option<DuScan>,
+    pub scanning: bool,
+    /// Entries seen so far by the running scan
+    pub scanned: u64,
+    pub root_path: PathBuf,
+    /// Indices of the children leading from the root to the directory shown
+    path: Vec<usize>,
+    /// Sizes and bars use the apparent sizes instead of the on-disk ones
+    pub apparent: bool,
+    /// Set on `d`, the next key confirms the delete
+    pub confirm_delete: bool,
+    pub state: TableState,
+    generation: u64,
+    cancel: Arc<AtomicBool>,
+    pub color_theme: ColorTheme,
+}
+
+// (device, inode) of the entry
+#[cfg(unix)]
+fn file_id(meta: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(unix)]
+fn disk_size(meta: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn disk_size(meta: &Metadata) -> u64 {
+    meta.len()
+}
+
+#[cfg(unix)]
+fn has_other_links(meta: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    meta.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn has_other_links(_meta: &Metadata) -> bool {
+    false
+}
+
+struct Scanner {
+    root_dev: Option<u64>,
+    cross_fs: bool,
+    /// Directories (device, inode) already scanned -> A directory reached twice through bind mounts is a loop
+    seen_dirs: HashSet<(u64, u64)>,
+    /// Files with more than one hard link already counted
+    seen_files: HashSet<(u64, u64)>,
+    unreadable: Vec<String>,
+    scanned: u64,
+    last_progress: Instant,
+    generation: u64,
+    cancel: Arc<AtomicBool>,
+    event_tx: Sender<AppEvent>,
+}
+
+impl Scanner {
+    fn leaf(name: String, meta: &Metadata) -> DuNode {
+        DuNode { name, is_dir: false, apparent: meta.len(), disk: disk_size(meta), files: 1, children: vec![], note: None }
+    }
+
+    // Scans the entry. Symlinks are never followed, they count as the link itself. `None` once cancelled.
+    fn scan(&mut self, path: &Path, name: String, meta: &Metadata) -> Option<DuNode> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        self.scanned += 1;
+        if self.last_progress.elapsed() >= PROGRESS_INTERVAL {
+            self.last_progress = Instant::now();
+            let _ = self.event_tx.send(AppEvent::DiskUsageProgress(self.generation, self.scanned));
+        }
+        if !meta.is_dir() {
+            if has_other_links(meta) && let Some(id) = file_id(meta) && !self.seen_files.insert(id) {
+                return Some(DuNode {
+                    apparent: 0,
+                    disk: 0,
+                    note: Some(String::from("hard link, counted once")),
+                    ..Scanner::leaf(name, meta)
+                });
+            }
+            return Some(Scanner::leaf(name, meta));
+        }
+        let mut node = DuNode { is_dir: true, files: 0, ..Scanner::leaf(name, meta) };
+        let id = file_id(meta);
+        if !self.cross_fs && id.map(|(dev, _ino)| dev) != self.root_dev {
+            node.note = Some(String::from("other filesystem, not scanned"));
+            return Some(node);
+        }
+        if let Some(id) = id && !self.seen_dirs.insert(id) {
+            node.note = Some(String::from("already scanned (loop), skipped"));
+            return Some(node);
+        }
+        let read_dir = match fs::read_dir(path) {
+            Ok(read_dir) => read_dir,
+            Err(err) => {
+                self.unreadable.push(format!("{}: {err}", path.to_string_lossy()));
+                node.note = Some(format!("unreadable: {err}"));
+                return Some(node);
+            },
+        };
+        for entry in read_dir.flatten() {
+            let entry_path = entry.path();
+            let Ok(entry_meta) = entry_path.symlink_metadata() else {
+                continue;
+            };
+            let child = self.scan(&entry_path, entry.file_name().to_string_lossy().to_string(), &entry_meta)?;
+            node.apparent += child.apparent;
+            node.disk += child.disk;
+            node.files += child.files;
+            node.children.push(child);
+        }
+        Some(node)
+    }
+}
+
+fn sort_tree(node: &mut DuNode, apparent: bool) {
+    if apparent {
+        node.children.sort_by(|a, b| b.apparent.cmp(&a.apparent).then_with(|| a.name.cmp(&b.name)));
+    } else {
+        node.children.sort_by(|a, b| b.disk.cmp(&a.disk).then_with(|| a.name.cmp(&b.name)));
+    }
+    for child in node.children.iter_mut() {
+        sort_tree(child, apparent);
+    }
+}
+
+// Removes the child `selected` of the directory at `path` and takes its totals off every directory on the way
+fn remove_child(node: &mut DuNode, path: &[usize], selected: usize) -> Option<DuNode> {
+    let removed = match path.split_first() {
+        Some((idx, rest)) => remove_child(node.children.get_mut(*idx)?, rest, selected)?,
+        None if selected < node.children.len() => node.children.remove(selected),
+        None => return None,
+    };
+    node.apparent = node.apparent.saturating_sub(removed.apparent);
+    node.disk = node.disk.saturating_sub(removed.disk);
+    node.files = node.files.saturating_sub(removed.files);
+    Some(removed)
+}
+
+fn bar(part: u64, total: u64) -> String {
+    let ratio = if total == 0 { 0.0 } else { part as f64 / total as f64 };
+    let filled = (ratio * BAR_WIDTH as f64).round() as usize;
+    format!("{:>5.1}% [{}{}]", ratio * 100.0, "#".repeat(filled), " ".repeat(BAR_WIDTH - filled.min(BAR_WIDTH)))
+}
+
+impl DiskUsage {
+    pub fn new(read_color_theme: ColorTheme) -> DiskUsage {
+        DiskUsage {
+            scan: None,
+            scanning: false,
+            scanned: 0,
+            root_path: PathBuf::default(),
+            path: vec![],
+            apparent: false,
+            confirm_delete: false,
+            state: TableState::default(),
+            generation: 0,
+            cancel: Arc::new(AtomicBool::new(false)),
+            color_theme: read_color_theme,
+        }
+    }
+
+    /// Scans the tree under `root` on a background thread. Other filesystems are only entered with `cross_fs`.
+    pub fn start(&mut self, root: PathBuf, cross_fs: bool, event_tx: Sender<AppEvent>) -> Result<(), String> {
+        let root_meta = root.symlink_metadata().map_err(|err| format!("Unable to read {}: {err}", root.to_string_lossy()))?;
+        self.stop();
+        self.generation += 1;
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.scan = None;
+        self.scanning = true;
+        self.scanned = 0;
+        self.root_path = root.clone();
+        self.path.clear();
+        self.confirm_delete = false;
+        self.state.select(None);
+
+        let mut scanner = Scanner {
+            root_dev: file_id(&root_meta).map(|(dev, _ino)| dev),
+            cross_fs,
+            seen_dirs: HashSet::new(),
+            seen_files: HashSet::new(),
+            unreadable: vec![],
+            scanned: 0,
+            last_progress: Instant::now(),
+            generation: self.generation,
+            cancel: Arc::clone(&self.cancel),
+            event_tx: event_tx.clone(),
+        };
+        let apparent = self.apparent;
+        thread::Builder::new()
+            .stack_size(SCAN_STACK_SIZE)
+            .spawn(move || {
+                let name = root.to_string_lossy().to_string();
+                if let Some(mut root_node) = scanner.scan(&root, name, &root_meta) {
+                    sort_tree(&mut root_node, apparent);
+                    let scan = DuScan { root_path: root, root: root_node, unreadable: scanner.unreadable };
+                    let _ = event_tx.send(AppEvent::DiskUsageDone(scanner.generation, Box::new(scan)));
+                }
+            })
+            .map_err(|err| format!("Unable to start the scan: {err}"))?;
+        Ok(())
+    }
+
+    /// Stops the scan, if one is going on
+    pub fn stop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.scanning = false;
+    }
+
+    pub fn set_progress(&mut self, generation: u64, scanned: u64) {
+        if generation == self.generation {
+            self.scanned = scanned;
+        }
+    }
+
+    pub fn finish(&mut self, generation: u64, scan: DuScan) {
+        if generation != self.generation {
+            return;
+        }
+        self.scanning = false;
+        self.scan = Some(scan);
+        self.state.select(Some(0));
+    }
+
+    // The directory shown, with its full path
+    fn current(&self) -> Option<(PathBuf, &DuNode)> {
+        let scan = self.scan.as_ref()?;
+        let mut dir_path = scan.root_path.clone();
+        let mut node = &scan.root;
+        for idx in &self.path {
+            node = node.children.get(*idx)?;
+            dir_path.push(&node.name);
+        }
+        Some((dir_path, node))
+    }
+
+    /// The entry under the cursor and its full path
+    pub fn selected(&self) -> Option<(PathBuf, &DuNode)> {
+        let (dir_path, current) = self.current()?;
+        let child = current.children.get(self.state.selected()?)?;
+        Some((dir_path.join(&child.name), child))
+    }
+
+    /// Takes the selected entry out of the tree and off the totals of the directories above it
+    pub fn remove_selected(&mut self) {
+        let (Some(selected), Some(scan)) = (self.state.selected(), self.scan.as_mut()) else {
+            return;
+        };
+        remove_child(&mut scan.root, &self.path, selected);
+        let len = self.current().map_or(0, |(_dir_path, current)| current.children.len());
+        self.state.select(if len == 0 { None } else { Some(selected.min(len - 1)) });
+    }
+
+    fn enter(&mut self) {
+        let Some(selected) = self.state.selected() else {
+            return;
+        };
+        if self.current().and_then(|(_dir_path, current)| current.children.get(selected)).is_some_and(|child| !child.children.is_empty()) {
+            self.path.push(selected);
+            self.state.select(Some(0));
+        }
+    }
+
+    fn leave(&mut self) {
+        if let Some(idx) = self.path.pop() {
+            self.state.select(Some(idx));
+        }
+    }
+
+    fn next_row(&mut self) {
+        let len = self.current().map_or(0, |(_dir_path, current)| current.children.len());
+        if len > 0 {
+            let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous_row(&mut self) {
+        let len = self.current().map_or(0, |(_dir_path, current)| current.children.len());
+        if len > 0 {
+            let i = self.state.selected().map_or(0, |i| if i == 0 { len - 1 } else { i - 1 });
+            self.state.select(Some(i));
+        }
+    }
+
+    fn toggle_apparent(&mut self) {
+        self.apparent = !self.apparent;
+        let selected_name = self.selected().map(|(_path, node)| node.name.clone());
+        if let Some(scan) = self.scan.as_mut() {
+            sort_tree(&mut scan.root, self.apparent);
+        }
+        // The directories on the way down are sorted again too, the view goes back to the top
+        self.path.clear();
+        let position = selected_name.and_then(|name| self.current()?.1.children.iter().position(|child| child.name == name));
+        self.state.select(Some(position.unwrap_or(0)));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let instructions = if self.confirm_delete {
+            Line::from(" Move the selected entry to the trash? (y/n) ").red().right_aligned()
+        } else {
+            Line::from(vec![
+                " <Enter>".blue().bold(),
+                " Open ".into(),
+                "<Backspace>".blue().bold(),
+                " Up ".into(),
+                "<a>".blue().bold(),
+                " Apparent / disk size ".into(),
+                "<d>".blue().bold(),
+                " Delete ".into(),
+                "<Esc>".blue().bold(),
+                " Close ".into(),
+            ]).right_aligned()
+        };
+        let block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title_bottom(instructions)
+            .title_style(Style::new().fg(self.color_theme.primary))
+            .border_style(Style::new().fg(self.color_theme.border));
+        let (Some(scan), Some((dir_path, current))) = (self.scan.as_ref(), self.current()) else {
+            let status = if self.scanning {
+                format!(" Scanning {}... {} entries", self.root_path.to_string_lossy(), self.scanned)
+            } else {
+                String::from(" The scan was stopped")
+            };
+            frame.render_widget(Paragraph::new(status).bg(Color::Black).block(block.title(" Disk usage ")), area);
+            return;
+        };
+        let title = format!(
+            " Disk usage: {}  {} on disk, {} apparent, {} files ",
+            dir_path.to_string_lossy(),
+            convert(current.disk as f64),
+            convert(current.apparent as f64),
+            current.files
+        );
+        let status = if scan.unreadable.is_empty() {
+            Line::default()
+        } else {
+            Line::from(format!(" {} unreadable director(y/ies) ", scan.unreadable.len())).red()
+        };
+        let total = if self.apparent { current.apparent } else { current.disk };
+        let rows = current.children.iter().map(|child| {
+            let size = if self.apparent { child.apparent } else { child.disk };
+            let name = if child.is_dir { format!("{}/", child.name) } else { child.name.clone() };
+            let style = if child.note.as_ref().is_some_and(|note| note.starts_with("unreadable")) {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(self.color_theme.primary)
+            };
+            Row::new([
+                Cell::from(convert(child.disk as f64)),
+                Cell::from(convert(child.apparent as f64)),
+                Cell::from(bar(size, total)),
+                Cell::from(if child.is_dir { child.files.to_string() } else { String::default() }),
+                Cell::from(name),
+                Cell::from(child.note.clone().unwrap_or_default()),
+            ])
+            .style(style)
+        });
+        let header = Row::new([
+            if self.apparent { "Disk" } else { "Disk ▼" },
+            if self.apparent { "Apparent ▼" } else { "Apparent" },
+            "Share",
+            "Files",
+            "Name",
+            "",
+        ])
+        .style(Style::default().fg(Color::Black).bg(self.color_theme.header));
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Length(11),
+                Constraint::Length(11),
+                Constraint::Length(BAR_WIDTH as u16 + 9),
+                Constraint::Length(8),
+                Constraint::Min(20),
+                Constraint::Length(32),
+            ],
+        )
+        .header(header)
+        .block(block.title(title).title_bottom(status))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED).fg(self.color_theme.selector))
+        .highlight_symbol(Text::from(vec![" ▶ ".into()]))
+        .bg(Color::Black)
+        .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(t, area, &mut self.state);
+    }
+}
+
+impl HandlesInput for DiskUsage {
+    fn handle_input(&mut self, event: Event) -> Result<(), String> {
+        if let Event::Key(key_event) = event && key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => self.next_row(),
+                KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
+                KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => self.enter(),
+                KeyCode::Backspace | KeyCode::Char('h') | KeyCode::Left => self.leave(),
+                KeyCode::Char('a') => self.toggle_apparent(),
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Moves the selected entry of the disk usage view to the trash and takes it off the totals
+pub fn disk_usage_delete(app: &mut App) {
+    let Some((path, _node)) = app.disk_usage.selected() else {
+        return;
+    };
+    match trash::delete(&path) {
+        Ok(()) => {
+            app.disk_usage.remove_selected();
+            app.undo.record(Operation::Trash(vec![path.clone()]));
+            app.log_panel.set_log(format!("Moved {} to the trash", path.to_string_lossy()));
+            app.explorer.reload();
+            if let Some(other) = app.other_pane.as_mut() {
+                other.explorer.reload();
+            }
+        },
+        Err(err) => app.log_panel.set_log(format!("Error in moving item to trash: {err}")),
+    }
+}
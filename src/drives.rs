@@ -1,9 +1,9 @@
 use std::path::PathBuf;
 
-use crossterm::event::{Event, KeyCode, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
 use ratatui::{
     Frame,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Position, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::Text,
     widgets::{
@@ -12,7 +12,7 @@ use ratatui::{
 };
 use sysinfo::Disks;
 
-use crate::{color_theme::ColorTheme, dependencies::HandlesInput};
+use crate::{color_theme::ColorTheme, dependencies::{HandlesInput, table_row_at}};
 
 #[derive(Debug, Clone)]
 pub struct DriveEntry {
@@ -25,6 +25,8 @@ pub struct Drives {
     pub state: TableState,
     pub scroll_state: ScrollbarState,
     pub in_focus: bool,
+    /// Where the table was last drawn -> Used to find the row clicked with the mouse
+    pub area: Rect,
     pub color_theme: ColorTheme,
 }
 
@@ -47,6 +49,7 @@ impl Drives {
             state: TableState::default().with_selected(0),
             scroll_state: ScrollbarState::new((&data_vec.len() - 1) * ITEM_HEIGHT),
             in_focus: false,
+            area: Rect::default(),
             color_theme: read_color_theme,
         }
     }
@@ -88,6 +91,7 @@ impl Drives {
     }
 
     pub fn create_drives_table(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
         let header_style = Style::default().fg(Color::Black).bg(self.color_theme.header);
         let selected_row_style = Style::default()
             .add_modifier(Modifier::REVERSED)
@@ -175,6 +179,21 @@ impl HandlesInput for Drives {
                     }
                 }
             }
+            Event::Mouse(mouse_event) => {
+                let position = Position::new(mouse_event.column, mouse_event.row);
+                let selected = self.state.selected().unwrap_or(0);
+                match mouse_event.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if let Some(idx) = table_row_at(self.area, self.state.offset(), position) && idx < self.drives.len() {
+                            self.state.select(Some(idx));
+                            self.scroll_state = self.scroll_state.position(idx * ITEM_HEIGHT);
+                        }
+                    },
+                    MouseEventKind::ScrollDown if selected + 1 < self.drives.len() => self.next_row(),
+                    MouseEventKind::ScrollUp if selected > 0 => self.previous_row(),
+                    _ => (),
+                }
+            },
             _ => {}
         }
         Ok(())
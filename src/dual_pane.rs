@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use crate::{App, CurrentWidget, conflict::start_operation, explorer::{Clipboard, Explorer}, history::History, tabs::Tab};
+
+fn pane_name(left: bool) -> Option<String> {
+    Some(String::from(if left { "Left" } else { "Right" }))
+}
+
+/// Name of the pane the active Explorer is shown in, `None` outside of the dual-pane mode
+pub fn active_pane_name(app: &App) -> Option<String> {
+    app.other_pane.as_ref().and_then(|_| pane_name(app.left_pane_active))
+}
+
+/// Opens the right pane on `right_path`, with the current Explorer as the left pane
+pub fn open_dual_pane(app: &mut App, right_path: PathBuf) {
+    let mut explorer: Explorer = app.explorer.clone();
+    explorer.marked.clear();
+    explorer.filter.clear();
+    explorer.in_focus = false;
+    explorer.pane_name = pane_name(false);
+    explorer.refresh(&right_path, app.include_hidden);
+    let history = History::new(&right_path, app.history.color_theme.clone());
+    app.explorer.pane_name = pane_name(true);
+    app.other_pane = Some(Tab { explorer, history });
+    app.left_pane_active = true;
+}
+
+/// Turns the dual-pane mode on or off. The active pane is kept when it is turned off.
+pub fn toggle_dual_pane(app: &mut App) {
+    if app.other_pane.take().is_some() {
+        app.explorer.pane_name = None;
+        app.left_pane_active = true;
+    } else {
+        open_dual_pane(app, app.explorer.root_path.clone());
+    }
+}
+
+/// Makes the other pane the active one
+pub fn switch_pane(app: &mut App) {
+    if let Some(other) = app.other_pane.as_mut() {
+        std::mem::swap(&mut app.explorer, &mut other.explorer);
+        std::mem::swap(&mut app.history, &mut other.history);
+        other.explorer.in_focus = false;
+        other.explorer.delete_ongoing = false;
+        app.explorer.in_focus = app.focus_on == CurrentWidget::Explorer;
+        app.left_pane_active = !app.left_pane_active;
+        app.explorer.reload();
+        app.path_field.set_value(String::from(app.explorer.root_path.to_string_lossy()));
+    }
+}
+
+/// Queues a job copying (or moving) the selected / marked items of the active pane into the directory of the other pane.
+/// The copy / paste clipboard is left untouched.
+pub fn transfer_to_other_pane(app: &mut App, is_move: bool) -> Result<(), String> {
+    let Some(other) = app.other_pane.as_mut() else {
+        return Err(String::from("The dual-pane mode is not turned on"));
+    };
+    if other.explorer.root_path == app.explorer.root_path {
+        return Err(String::from("Both the panes are in the same directory"));
+    }
+    let mut transfer = Clipboard::default();
+    app.explorer.handle_copy(&mut transfer, is_move, false);
+    let (kind, items) = transfer.paste_items(&other.explorer.root_path);
+    start_operation(app, kind, items);
+    Ok(())
+}
@@ -1,17 +1,18 @@
-use std::{path::PathBuf, process::Command};
-use crossterm::event::{Event, KeyCode, KeyEventKind};
+use std::{collections::BTreeSet, fs, path::{Path, PathBuf}, process::Command, time::SystemTime};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use tui_input::backend::crossterm::EventHandler;
 use ratatui::{
     Frame,
-    layout::{Constraint, Margin, Rect},
+    layout::{Constraint, Margin, Position, Rect},
     style::{Color, Modifier, Style, Stylize},
-    text::Text,
+    text::{Line, Span, Text},
     widgets::{Block, Cell, HighlightSpacing, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState},
 };
 
-use crate::{App, color_theme::{ColorTheme}, dependencies::delete, open_files::{FileOptions, handle_file_open, read_file_options}};
+use crate::{App, color_theme::{ColorTheme}, columns::{Column, ColumnKind, cell_text, default_columns, toggle_columns}, conflict::OperationKind, filter::Filter, icons::nerd_icon, jobs::{JobItem, JobKind, JobOrigin, Jobs}, open_files::{FileOptions, handle_file_open, read_file_options}, sort::{SortKey, SortOptions, sort_entries}};
 use crate::{
-    dependencies::{HandlesInput, copy_directory, copy_file},
-    file_deps::get_data,
+    dependencies::{HandlesInput, table_row_at},
+    file_deps::{entry_type, get_data},
 };
 
 #[derive(Debug, Clone)]
@@ -19,61 +20,181 @@ pub struct FileEntry {
     pub e_type: EntryType,
     pub name: String,
     pub size: String,
+    pub size_bytes: u64,
     pub modified_at: String,
+    pub modified: Option<SystemTime>,
+    pub created: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
     pub hidden: bool,
     pub is_exec: bool,
+    /// Type and permission bits, on Unix
+    pub mode: Option<u32>,
+    /// (uid, gid), on Unix
+    pub owner: Option<(u32, u32)>,
+    /// Inode number and hard link count, on Unix
+    pub inode: Option<u64>,
+    pub links: Option<u64>,
+    /// Where it points to, for a symlink
+    pub link: Option<LinkTarget>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EntryType {
     File,
     Dir,
+    /// A symlink, whatever it points to
+    Link,
+    /// A named pipe
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
 }
 
+impl EntryType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryType::File => "file",
+            EntryType::Dir => "directory",
+            EntryType::Link => "symlink",
+            EntryType::Fifo => "named pipe (FIFO)",
+            EntryType::Socket => "socket",
+            EntryType::BlockDevice => "block device",
+            EntryType::CharDevice => "character device",
+        }
+    }
+
+    /// A FIFO, a socket or a device -> Never opened or read, as that could block or have side effects
+    pub fn is_special(&self) -> bool {
+        matches!(self, EntryType::Fifo | EntryType::Socket | EntryType::BlockDevice | EntryType::CharDevice)
+    }
+}
+
+/// What a symlink points to
+#[derive(Debug, Clone)]
+pub struct LinkTarget {
+    /// The path stored in the link. A relative one is relative to the directory of the link.
+    pub path: PathBuf,
+    /// The target is a directory
+    pub is_dir: bool,
+    /// Nothing is found at the target
+    pub broken: bool,
+}
+
+/// Items staged by a copy / cut operation, waiting to be pasted. Shared by all the tabs.
+#[derive(Debug, Default)]
+pub struct Clipboard {
+    pub items: Vec<(PathBuf, EntryType)>,
+    pub is_cut: bool,
+    /// The links copied are pasted as what they point to, instead of as links
+    pub follow_links: bool,
+}
+
+impl Clipboard {
+    /// (source, destination) pairs to paste the items into `dest_dir`
+    pub fn paste_items(&self, dest_dir: &Path) -> (OperationKind, Vec<(PathBuf, PathBuf)>) {
+        let kind = if self.is_cut { OperationKind::Move } else { OperationKind::Copy };
+        let items = self
+            .items
+            .iter()
+            .filter_map(|(src_file_path, file_type)| {
+                let file_name = src_file_path.file_name()?;
+                // A broken link is pasted as a link
+                let src = match file_type {
+                    EntryType::Link if self.follow_links => src_file_path.canonicalize().unwrap_or(src_file_path.clone()),
+                    _ => src_file_path.clone(),
+                };
+                Some((src, dest_dir.join(file_name)))
+            })
+            .collect();
+        (kind, items)
+    }
+
+    /// Empties a cut clipboard, once its items are being moved
+    pub fn clear_cut(&mut self) {
+        if self.is_cut {
+            self.items.clear();
+            self.is_cut = false;
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Explorer {
     pub root_path: PathBuf,
     pub include_hidden: bool,
-    pub copy_src_path: Option<PathBuf>,
+    /// Entries shown in the table -> All the entries of the directory unless a filter is applied
     pub files: Vec<FileEntry>,
-    pub copied_item: Option<EntryType>,
-    pub file_is_cut: bool,
+    /// All the entries of the directory
+    pub unfiltered: Vec<FileEntry>,
+    pub filter: Filter,
+    /// Absolute paths of the marked entries. Kept across directory changes.
+    pub marked: BTreeSet<PathBuf>,
     pub file_open_options: FileOptions,
     pub state: TableState,
     pub scroll_state: ScrollbarState,
     pub in_focus: bool,
     pub delete_ongoing: bool,
+    pub sort: SortOptions,
+    /// Columns of the table, in their order
+    pub columns: Vec<Column>,
+    /// Nerd Font icons in the Type column instead of the glyphs
+    pub nerd_font_icons: bool,
+    /// Name of the pane ("Left" / "Right") in the dual-pane mode. The path is shown next to it in the title.
+    pub pane_name: Option<String>,
+    /// Where the table and the scrollbar were last drawn -> Used to find what the mouse points at
+    pub area: Rect,
+    pub scrollbar_area: Rect,
+    /// Set while the scrollbar is dragged with the mouse
+    dragging_scrollbar: bool,
     pub color_theme: ColorTheme,
 }
 
 impl FileEntry {
-    fn ref_array(&self) -> [String; 4] {
-        let type_of_entry: String;
-        let display_name: String;
-        if self.e_type == EntryType::Dir {
-            type_of_entry = String::from(" 🖿 ");
-            display_name = format!("{}/", self.name);
-        } else {
-            type_of_entry = String::from(" 🗎 ");
-            display_name = self.name.clone();
-        };
-        [
-            type_of_entry,
-            display_name,
-            self.size.clone(),
-            self.modified_at.clone(),
-        ]
+    /// Glyph shown in the Type column
+    pub fn glyph(&self) -> &'static str {
+        match self.e_type {
+            EntryType::Dir => " 🖿 ",
+            EntryType::File => " 🗎 ",
+            EntryType::Link => " ⇢ ",
+            EntryType::Fifo => " ≋ ",
+            EntryType::Socket => " ⊛ ",
+            EntryType::BlockDevice => " ▦ ",
+            EntryType::CharDevice => " ◧ ",
+        }
+    }
+
+    /// `name/` for a directory, `name -> target` for a symlink
+    pub fn display_name(&self) -> String {
+        match (&self.e_type, &self.link) {
+            (EntryType::Dir, _) => format!("{}/", self.name),
+            (EntryType::Link, Some(link)) => format!("{} -> {}", self.name, link.path.to_string_lossy()),
+            _ => self.name.clone(),
+        }
+    }
+
+    /// A directory, or a symlink to one -> Enter goes into it
+    pub fn leads_to_dir(&self) -> bool {
+        self.e_type == EntryType::Dir || self.link.as_ref().is_some_and(|link| link.is_dir)
+    }
+
+    pub fn is_broken_link(&self) -> bool {
+        self.link.as_ref().is_some_and(|link| link.broken)
     }
 }
 
 const ITEM_HEIGHT: usize = 1;
+/// Rows moved by a turn of the mouse wheel
+const WHEEL_STEP: usize = 3;
 
 impl Explorer {
-    pub fn new(path: &PathBuf, config_path: Option<PathBuf>, include_hidden: bool, read_color_theme: ColorTheme) -> Explorer {
+    pub fn new(path: &PathBuf, config_path: Option<PathBuf>, include_hidden: bool, sort: SortOptions, read_color_theme: ColorTheme) -> Explorer {
         const ITEM_HEIGHT: usize = 1;
-        let render_data: Vec<FileEntry>;
+        let mut render_data: Vec<FileEntry>;
         let render_scrollbar_state: ScrollbarState;
         if let Ok(data_vec) = get_data(path, include_hidden, false, false, false) {
             render_data = data_vec;
+            sort_entries(&mut render_data, &sort);
             render_scrollbar_state = ScrollbarState::new((render_data.len() - 1) * ITEM_HEIGHT);
         } else {
             render_data = vec![];
@@ -82,31 +203,146 @@ impl Explorer {
         return Explorer {
             root_path: PathBuf::from(path),
             include_hidden: include_hidden,
-            files: render_data,
-            copy_src_path: None,
-            copied_item: None,
-            file_is_cut: false,
+            files: render_data.clone(),
+            unfiltered: render_data,
+            filter: Filter::default(),
+            marked: BTreeSet::new(),
             file_open_options: read_file_options(config_path),
             state: TableState::default().with_selected(0),
             scroll_state: render_scrollbar_state,
             in_focus: true,
             delete_ongoing: false,
+            sort,
+            columns: default_columns(),
+            nerd_font_icons: false,
+            pane_name: None,
+            area: Rect::default(),
+            scrollbar_area: Rect::default(),
+            dragging_scrollbar: false,
             color_theme: read_color_theme,
         };
     }
 
     pub fn refresh(&mut self, path: &PathBuf, include_hidden: bool) {
+        // A filter only applies to the directory it was typed in
+        if self.root_path != *path {
+            self.filter.clear();
+        }
         self.root_path = path.clone();
         if let Ok(data_vec) = get_data(path, include_hidden, false, false, false) {
-            self.files = data_vec;
-            self.scroll_state = ScrollbarState::new((self.files.len() - 1) * ITEM_HEIGHT);
+            self.unfiltered = data_vec;
+            sort_entries(&mut self.unfiltered, &self.sort);
         } else {
-            self.files = vec![];
+            self.unfiltered = vec![];
         }
+        self.apply_filter();
         self.state.select(Some(0));
     }
 
+    /// Reads the directory again while keeping the cursor on the same entry
+    pub fn reload(&mut self) {
+        let selected_name = self.selected_name();
+        self.refresh(&self.root_path.clone(), self.include_hidden);
+        if let Some(name) = selected_name {
+            self.select_name(&name);
+        }
+    }
+
+    // Rebuilds `files` from the unfiltered listing
+    fn apply_filter(&mut self) {
+        self.files = if self.filter.applied && !self.filter.is_empty() {
+            self.unfiltered
+                .iter()
+                .filter(|entry| self.filter.matches(&entry.name))
+                .cloned()
+                .collect()
+        } else {
+            self.unfiltered.clone()
+        };
+        self.scroll_state = ScrollbarState::new(self.files.len().saturating_sub(1) * ITEM_HEIGHT);
+    }
+
+    pub fn selected_name(&self) -> Option<String> {
+        self.state.selected().and_then(|idx| self.files.get(idx)).map(|entry| entry.name.clone())
+    }
+
+    // Re-applies the filter while keeping the cursor on the same entry if it is still listed
+    fn update_filter(&mut self) {
+        let selected_name = self.selected_name();
+        self.filter.update();
+        self.apply_filter();
+        self.state.select(if self.files.is_empty() { None } else { Some(0) });
+        if let Some(name) = selected_name {
+            self.select_name(&name);
+        }
+    }
+
+    /// Brings back the full listing. The pattern is kept to jump between the matches with `n` / `N`.
+    fn close_filter(&mut self) {
+        self.filter.editing = false;
+        self.filter.applied = false;
+        self.update_filter();
+    }
+
+    fn jump_to_match(&mut self, forward: bool) {
+        if self.filter.is_empty() || self.files.is_empty() {
+            return;
+        }
+        let len = self.files.len();
+        let start = self.state.selected().unwrap_or(0);
+        for step in 1..=len {
+            let idx = if forward { (start + step) % len } else { (start + len - step) % len };
+            if self.filter.matches(&self.files[idx].name) {
+                self.state.select(Some(idx));
+                self.scroll_state = self.scroll_state.position(idx * ITEM_HEIGHT);
+                return;
+            }
+        }
+    }
+
+    /// Re-sorts the listing with the given options while keeping the cursor on the same entry
+    pub fn set_sort(&mut self, sort: SortOptions) {
+        let selected_name = self.selected_name();
+        self.sort = sort;
+        sort_entries(&mut self.unfiltered, &self.sort);
+        sort_entries(&mut self.files, &self.sort);
+        if let Some(name) = selected_name {
+            self.select_name(&name);
+        }
+    }
+
+    /// Moves the cursor to the entry with the given name, if it is present in the listing
+    pub fn select_name(&mut self, name: &str) {
+        if let Some(idx) = self.files.iter().position(|entry| entry.name == name) {
+            self.state.select(Some(idx));
+            self.scroll_state = self.scroll_state.position(idx * ITEM_HEIGHT);
+        }
+    }
+
+    /// Moves the cursor to the row `idx`, or to the last row if there are fewer
+    pub fn select_index(&mut self, idx: usize) {
+        if self.files.is_empty() {
+            return;
+        }
+        let idx = idx.min(self.files.len() - 1);
+        self.state.select(Some(idx));
+        self.scroll_state = self.scroll_state.position(idx * ITEM_HEIGHT);
+    }
+
+    // Selects the row matching the height of the mouse on the scrollbar
+    fn scroll_to(&mut self, y: u16) {
+        let track = self.scrollbar_area.inner(Margin { vertical: 1, horizontal: 0 });
+        if track.height < 2 {
+            return;
+        }
+        let offset = y.clamp(track.top(), track.bottom() - 1) - track.top();
+        self.select_index(offset as usize * self.files.len().saturating_sub(1) / (track.height - 1) as usize);
+    }
+
     pub fn next_row(&mut self) {
+        if self.files.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.files.len() - 1 {
@@ -122,6 +358,9 @@ impl Explorer {
     }
 
     pub fn previous_row(&mut self) {
+        if self.files.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -137,6 +376,16 @@ impl Explorer {
     }
 
     pub fn create_explorer_table(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
+        let name = match &self.pane_name {
+            Some(pane_name) => format!("{}: {}", pane_name, self.root_path.to_string_lossy()),
+            None => String::from("Explorer"),
+        };
+        let title = if self.marked.is_empty() {
+            format!(" {} ", name)
+        } else {
+            format!(" {} [{} marked] ", name, self.marked.len())
+        };
         let header_style = Style::default().fg(Color::Black).bg(self.color_theme.header);
         let selected_row_style = Style::default()
             .add_modifier(Modifier::REVERSED)
@@ -145,9 +394,27 @@ impl Explorer {
         let selected_cell_style = Style::default()
             .add_modifier(Modifier::REVERSED)
             .fg(self.color_theme.selector);
+        let highlight_style = Style::default().fg(Color::Black).bg(self.color_theme.border);
 
-        let header = ["Type", "Name", "Size", "Modified At"]
-            .into_iter()
+        let sorted_on = |column: &str, key: SortKey| {
+            if self.sort.key == key {
+                format!("{column} {}", self.sort.arrow())
+            } else {
+                String::from(column)
+            }
+        };
+        let ext_shown = self.columns.iter().any(|column| column.kind == ColumnKind::Extension);
+        let header = self.columns.iter().map(|column| match column.kind {
+            // The Name column tells about the extension sort when there is no Extension column
+            ColumnKind::Name if self.sort.key == SortKey::Extension && !ext_shown => format!("Name (ext) {}", self.sort.arrow()),
+            ColumnKind::Name => sorted_on("Name", SortKey::Name),
+            ColumnKind::Extension => sorted_on("Ext", SortKey::Extension),
+            ColumnKind::Size => sorted_on("Size", SortKey::Size),
+            ColumnKind::Modified => sorted_on("Modified At", SortKey::Modified),
+            kind => String::from(kind.title()),
+        });
+        let header = std::iter::once(String::default())
+            .chain(header)
             .map(Cell::from)
             .collect::<Row>()
             .style(header_style)
@@ -157,29 +424,53 @@ impl Explorer {
                 0 => self.color_theme.explorer_bg_1,
                 _ => self.color_theme.explorer_bg_2,
             };
-            let item = data.ref_array();
-            item.into_iter()
-                .map(|content| Cell::from(Text::from(format!("{content}"))))
+            let mark = if self.is_marked(data) { String::from("✓") } else { String::default() };
+            let mut cells = vec![Cell::from(mark)];
+            for column in &self.columns {
+                let text = match column.kind {
+                    ColumnKind::Type if self.nerd_font_icons => nerd_icon(data),
+                    kind => cell_text(data, kind),
+                };
+                cells.push(match self.filter.match_range(&data.name) {
+                    Some((start, end)) if column.kind == ColumnKind::Name => Cell::from(Line::from(vec![
+                        Span::raw(text[..start].to_string()),
+                        Span::styled(text[start..end].to_string(), highlight_style),
+                        Span::raw(text[end..].to_string()),
+                    ])),
+                    _ => Cell::from(text),
+                });
+            }
+            let style = Style::new().fg(self.color_theme.primary).bg(color);
+            cells
+                .into_iter()
                 .collect::<Row>()
-                .style(Style::new().fg(self.color_theme.primary).bg(color))
+                .style(style.patch(self.color_theme.files.style(data).unwrap_or_default()))
                 .height(1)
         });
         // let bar = " █ ";
         let bar = " ▶ ";
-        let t = Table::new(
-            rows,
-            [
-                // + 1 is for padding.
-                Constraint::Length(5),
-                Constraint::Min(40),
-                Constraint::Min(8),
-                Constraint::Min(15),
-            ],
-        )
+        let filter_line = if self.filter.editing || !self.filter.is_empty() {
+            let mut spans = vec![
+                Span::raw(format!(" /{} ", self.filter.pattern())),
+                Span::styled(format!("[{}] ", self.filter.mode.label()), Style::default().fg(self.color_theme.primary)),
+            ];
+            if self.filter.is_invalid() {
+                spans.push(Span::styled("(invalid pattern) ", Style::default().fg(Color::Red)));
+            } else if self.filter.applied {
+                spans.push(Span::raw(format!("{}/{} ", self.files.len(), self.unfiltered.len())));
+            }
+            Line::from(spans)
+        } else {
+            Line::default()
+        };
+        // + 1 is for padding.
+        let widths = std::iter::once(Constraint::Length(1)).chain(self.columns.iter().map(Column::constraint));
+        let t = Table::new(rows, widths)
         .block(
             Block::bordered()
                 .border_type(ratatui::widgets::BorderType::Rounded)
-                .title(" Explorer ")
+                .title(title)
+                .title_bottom(filter_line)
                 .border_style(if self.in_focus {
                     Style::default().fg(self.color_theme.border)
                 } else {
@@ -194,9 +485,16 @@ impl Explorer {
         .bg(Color::Black)
         .highlight_spacing(HighlightSpacing::Always);
         frame.render_stateful_widget(t, area, &mut self.state);
+
+        if self.filter.editing {
+            // Cursor is placed after the border and the `/` of the filter line
+            let x = area.x + 3 + self.filter.input.visual_cursor() as u16;
+            frame.set_cursor_position((x.min(area.right().saturating_sub(2)), area.bottom().saturating_sub(1)));
+        }
     }
 
     pub fn render_scrollbar(&mut self, frame: &mut Frame, area: Rect) {
+        self.scrollbar_area = area;
         frame.render_stateful_widget(
             Scrollbar::default()
                 .orientation(ScrollbarOrientation::VerticalRight)
@@ -210,110 +508,112 @@ impl Explorer {
         );
     }
 
-    fn handle_copy(&mut self) {
-        if let Some(idx) = self.state.selected() {
-            let mut file_path = self.root_path.clone();
-            file_path.push(&self.files[idx].name);
-            self.copy_src_path = Some(file_path);
-            if self.files[idx].e_type == EntryType::File {
-                self.copied_item = Some(EntryType::File)
-            } else {
-                self.copied_item = Some(EntryType::Dir)
-            }
+    /// Path of the entry under the cursor
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        let idx = self.state.selected()?;
+        self.files.get(idx).map(|entry| self.root_path.join(&entry.name))
+    }
+
+    /// The items an operation should act on -> The marked items if there are any, else the item under the cursor
+    pub fn selected_paths(&self) -> Vec<PathBuf> {
+        if self.marked.is_empty() {
+            self.selected_path().into_iter().collect()
+        } else {
+            self.marked.iter().cloned().collect()
         }
     }
 
-    fn handle_paste(&mut self) -> Result<(), String> {
-        if self.copy_src_path != None {
-            match self.copied_item.clone() {
-                Some(file_type) => {
-                    let mut paste_path = self.root_path.clone();
-                    if let Some(src_file_path) = self.copy_src_path.clone() {
-                        match src_file_path.file_name() {
-                            Some(file_name) => {
-                                let mut file_name_str =
-                                    String::from(file_name.to_str().unwrap_or("default"));
-                                loop {
-                                    let mut flag: bool = false;
-                                    for entry in &self.files {
-                                        if entry.name == file_name_str {
-                                            file_name_str.insert_str(0, "Copy-");
-                                            flag = true;
-                                        }
-                                    }
-                                    if !flag {
-                                        break;
-                                    }
-                                }
-                                paste_path.push(file_name_str)
-                            }
-                            None => {}
-                        }
-                        if file_type.to_owned() == EntryType::File {
-                            if let Err(_err) = copy_file(&src_file_path, &paste_path) {
-                                return Err(String::from("Paste Operation failed: File could not be pasted"))
-                            }
-                        } else {
-                            if let Err(_err) = copy_directory(&src_file_path, &paste_path) {
-                                return Err(String::from("Paste Operation failed: Directory could not be pasted"))
-                            }
-                        }
-                        if self.file_is_cut == true {
-                            delete(&src_file_path, file_type.to_owned())?;
-                            self.file_is_cut = false;
-                        }
-                        self.refresh(&self.root_path.clone(), self.include_hidden);
-                    }
-                }
-                None => {}
+    pub fn is_marked(&self, entry: &FileEntry) -> bool {
+        !self.marked.is_empty() && self.marked.contains(&self.root_path.join(&entry.name))
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(file_path) = self.selected_path() {
+            if !self.marked.remove(&file_path) {
+                self.marked.insert(file_path);
             }
+            self.next_row();
         }
-        Ok(())
     }
 
-    pub fn handle_delete(&mut self) -> Result<(), String> {
-        if let Some(idx) = self.state.selected() {
-            let mut file_path = self.root_path.clone();
-            file_path = file_path.join(self.files[idx].name.clone());
-            delete(&file_path, self.files[idx].e_type.clone())?;
+    fn mark_all(&mut self) {
+        for entry in &self.files {
+            self.marked.insert(self.root_path.join(&entry.name));
         }
-        self.refresh(&self.root_path.clone(), self.include_hidden);
-        Ok(())
     }
 
-    fn move_item_to_trash(&mut self) -> Result<(), String> {
-        if let Some(idx) = self.state.selected() {
-            let mut file_path = self.root_path.clone();
-            file_path = file_path.join(self.files[idx].name.clone());
-            match trash::delete(file_path) {
-                Ok(_) => {
-                    self.refresh(&self.root_path.clone(), self.include_hidden);
-                    return Ok(());
-                },
-                Err(e) => {
-                    return Err(format!("Error in moving item to trash: {}", e));
-                }
+    fn invert_marks(&mut self) {
+        for entry in &self.files {
+            let file_path = self.root_path.join(&entry.name);
+            if !self.marked.remove(&file_path) {
+                self.marked.insert(file_path);
             }
         }
-        Ok(())
+    }
+
+    /// Stages the selected (or marked) items. With `follow_links`, the links are pasted as what they point to.
+    pub fn handle_copy(&mut self, clipboard: &mut Clipboard, is_cut: bool, follow_links: bool) {
+        clipboard.is_cut = is_cut;
+        clipboard.follow_links = follow_links;
+        clipboard.items = self
+            .selected_paths()
+            .into_iter()
+            .map(|file_path| {
+                let file_type = match file_path.symlink_metadata() {
+                    Ok(meta) if meta.is_symlink() => EntryType::Link,
+                    Ok(meta) if meta.is_dir() => EntryType::Dir,
+                    _ => EntryType::File,
+                };
+                (file_path, file_type)
+            })
+            .collect();
+        self.marked.clear();
+    }
+
+    /// Queues a job permanently deleting the selected (or marked) items
+    pub fn handle_delete(&mut self, jobs: &mut Jobs) {
+        let items = self.selected_paths().into_iter().map(|file_path| JobItem::new(file_path.clone(), file_path)).collect();
+        jobs.enqueue(JobKind::Delete, items, JobOrigin::User);
+        self.marked.clear();
+    }
+
+    /// Moves the selected (or marked) items to the trash. Returns the paths that were trashed.
+    pub fn move_item_to_trash(&mut self) -> Result<Vec<PathBuf>, String> {
+        let to_trash = self.selected_paths();
+        if to_trash.is_empty() {
+            return Ok(to_trash);
+        }
+        match trash::delete_all(&to_trash) {
+            Ok(_) => {
+                self.marked.clear();
+                self.reload();
+                Ok(to_trash)
+            },
+            Err(e) => Err(format!("Error in moving item to trash: {}", e)),
+        }
     }
 }
 
 pub fn explorer_handle_enter(app: &mut App) -> Result<(), String> {
     if let Some(selected_idx) = app.explorer.state.selected() {
         let entry = &app.explorer.files[selected_idx];
-        if entry.e_type == EntryType::Dir {
+        if entry.leads_to_dir() {
             let mut dir_path = PathBuf::from(app.path_field.input.value());
             dir_path.push(entry.name.clone());
-            app.path_field.set_value(String::from(dir_path.to_string_lossy()));
-            app.explorer.refresh(&dir_path, app.include_hidden);
+            app.navigate_to(dir_path);
+        } else if let Some(link) = entry.link.as_ref().filter(|link| link.broken) {
+            app.log_panel.set_log(format!("The link is broken, {} doesn't exist", link.path.to_string_lossy()));
         } else {
             let mut file_path = PathBuf::from(app.path_field.input.value());
             file_path.push(entry.name.clone());
-            if app.explorer.files[selected_idx].is_exec {
+            // Checked through the links too. Opening a FIFO or a device could hang the program opening it.
+            let target_type = fs::metadata(&file_path).ok().map(|meta| entry_type(meta.file_type()));
+            if let Some(target_type) = target_type.filter(|target_type| target_type.is_special()) {
+                app.log_panel.set_log(format!("{} is a {}, it can't be opened", entry.name, target_type.label()));
+            } else if app.explorer.files[selected_idx].is_exec {
                 let _ = Command::new(file_path).spawn();
             } else {
-                handle_file_open(&file_path, app.explorer.file_open_options.clone())?;
+                handle_file_open(&file_path, &app.explorer.file_open_options)?;
             }
         }
     }
@@ -324,32 +624,77 @@ impl HandlesInput for Explorer {
     fn handle_input(&mut self, event: Event) -> Result<(), String> {
         match event {
             Event::Key(key_event) => {
-                if key_event.kind == KeyEventKind::Press {
+                if key_event.kind == KeyEventKind::Press && self.filter.editing {
+                    match key_event.code {
+                        KeyCode::Esc => self.close_filter(),
+                        KeyCode::Enter => self.filter.editing = false,
+                        KeyCode::Tab => {
+                            self.filter.mode = self.filter.mode.next();
+                            self.update_filter();
+                        }
+                        KeyCode::Down => self.next_row(),
+                        KeyCode::Up => self.previous_row(),
+                        _ => {
+                            self.filter.input.handle_event(&Event::Key(key_event));
+                            self.update_filter();
+                        }
+                    }
+                } else if key_event.kind == KeyEventKind::Press {
                     match key_event.code {
                         KeyCode::Char('j') | KeyCode::Down => self.next_row(),
                         KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
                         KeyCode::Char('r') => {
                             self.refresh(&self.root_path.clone(), self.include_hidden);
                         }
-                        KeyCode::Delete => self.move_item_to_trash()?,
-                        KeyCode::Char('c') => self.handle_copy(),
-                        KeyCode::Char('v') => self.handle_paste()?,
-                        KeyCode::Char('x') => {
-                            self.handle_copy();
-                            self.file_is_cut = true;
+                        KeyCode::Char(' ') => self.toggle_mark(),
+                        KeyCode::Char('A') => self.mark_all(),
+                        KeyCode::Char('I') => self.invert_marks(),
+                        KeyCode::Char('/') => {
+                            self.filter.input.reset();
+                            self.filter.editing = true;
+                            self.filter.applied = true;
+                            self.update_filter();
+                        }
+                        KeyCode::Char('n') => self.jump_to_match(true),
+                        KeyCode::Char('N') => self.jump_to_match(false),
+                        KeyCode::Esc => {
+                            if self.filter.applied {
+                                self.close_filter();
+                            } else if !self.filter.is_empty() {
+                                self.filter.clear();
+                            } else {
+                                self.marked.clear();
+                            }
                         }
+                        KeyCode::Char('s') => self.set_sort(SortOptions { key: self.sort.key.next(), ..self.sort }),
+                        KeyCode::Char('S') => self.set_sort(SortOptions { descending: !self.sort.descending, ..self.sort }),
+                        KeyCode::Char('M') => toggle_columns(&mut self.columns, &[ColumnKind::Mode]),
+                        KeyCode::Char('O') => toggle_columns(&mut self.columns, &[ColumnKind::Owner, ColumnKind::Group]),
+                        KeyCode::Char('D') => self.set_sort(SortOptions { dirs_first: !self.sort.dirs_first, ..self.sort }),
                         _ => {}
                     }
                 }
             },
-            // Event::Mouse(mouse_event) => {
-            //     match mouse_event.kind {
-            //         MouseEventKind::Down(MouseButton::Left) => {
-                        
-            //         },
-            //         _ => (),
-            //     }
-            // }
+            Event::Mouse(mouse_event) => {
+                let position = Position::new(mouse_event.column, mouse_event.row);
+                let selected = self.state.selected().unwrap_or(0);
+                match mouse_event.kind {
+                    MouseEventKind::Down(MouseButton::Left) if self.scrollbar_area.contains(position) => {
+                        self.dragging_scrollbar = true;
+                        self.scroll_to(position.y);
+                    },
+                    MouseEventKind::Drag(MouseButton::Left) if self.dragging_scrollbar => self.scroll_to(position.y),
+                    MouseEventKind::Up(MouseButton::Left) => self.dragging_scrollbar = false,
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if let Some(idx) = table_row_at(self.area, self.state.offset(), position) && idx < self.files.len() {
+                            self.select_index(idx);
+                        }
+                    },
+                    MouseEventKind::ScrollDown => self.select_index(selected + WHEEL_STEP),
+                    MouseEventKind::ScrollUp => self.select_index(selected.saturating_sub(WHEEL_STEP)),
+                    _ => {}
+                }
+            },
             _ => {}
         }
         Ok(())
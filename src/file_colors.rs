@@ -0,0 +1,184 @@
+use std::{collections::HashMap, path::Path};
+
+use lscolors::{Indicator, LsColors};
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+
+use crate::explorer::{EntryType, FileEntry};
+
+const ARCHIVES: [&str; 12] = ["zip", "tar", "gz", "tgz", "xz", "bz2", "zst", "7z", "rar", "deb", "rpm", "iso"];
+const IMAGES: [&str; 9] = ["png", "jpg", "jpeg", "gif", "bmp", "svg", "webp", "ico", "tiff"];
+const MEDIA: [&str; 10] = ["mp3", "flac", "wav", "ogg", "m4a", "mp4", "mkv", "webm", "avi", "mov"];
+
+/// The `[file_colors]` table of color_theme.toml. A color left out keeps the built-in one.
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default)]
+pub struct FileColorsConfig {
+    pub directory: Option<String>,
+    pub symlink: Option<String>,
+    pub broken_link: Option<String>,
+    pub executable: Option<String>,
+    pub fifo: Option<String>,
+    pub socket: Option<String>,
+    pub device: Option<String>,
+    /// Extension (without the dot) -> color, on top of the built-in ones
+    pub extensions: HashMap<String, String>,
+}
+
+/// Colors of the Explorer entries by type and extension
+#[derive(Debug, Default, Clone)]
+pub struct FileColors {
+    /// Read from `LS_COLORS`. Used instead of the colors below when it is set.
+    ls_colors: Option<LsColors>,
+    directory: Color,
+    symlink: Color,
+    broken_link: Color,
+    executable: Color,
+    fifo: Color,
+    socket: Color,
+    device: Color,
+    /// Lowercase extension -> color
+    extensions: HashMap<String, Color>,
+}
+
+fn parse_color(value: Option<String>, default: Color) -> Color {
+    value.and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+impl FileColors {
+    pub fn new(config: FileColorsConfig) -> FileColors {
+        let mut extensions: HashMap<String, Color> = HashMap::new();
+        for (group, color) in [(&ARCHIVES[..], Color::LightRed), (&IMAGES[..], Color::LightMagenta), (&MEDIA[..], Color::Magenta)] {
+            for ext in group {
+                extensions.insert(ext.to_string(), color);
+            }
+        }
+        for (ext, color) in config.extensions {
+            if let Ok(color) = color.parse() {
+                extensions.insert(ext.trim_start_matches('.').to_lowercase(), color);
+            }
+        }
+        FileColors {
+            ls_colors: LsColors::from_env(),
+            directory: parse_color(config.directory, Color::LightBlue),
+            symlink: parse_color(config.symlink, Color::LightCyan),
+            broken_link: parse_color(config.broken_link, Color::Red),
+            executable: parse_color(config.executable, Color::LightGreen),
+            fifo: parse_color(config.fifo, Color::Yellow),
+            socket: parse_color(config.socket, Color::Magenta),
+            device: parse_color(config.device, Color::LightYellow),
+            extensions,
+        }
+    }
+
+    /// Style of the row of the entry. `None` for a plain file without a color -> The primary color of the theme.
+    pub fn style(&self, entry: &FileEntry) -> Option<Style> {
+        match &self.ls_colors {
+            Some(ls_colors) => ls_style(ls_colors, entry),
+            None => self.theme_color(entry).map(|color| Style::new().fg(color)),
+        }
+    }
+
+    fn theme_color(&self, entry: &FileEntry) -> Option<Color> {
+        match entry.e_type {
+            EntryType::Dir => Some(self.directory),
+            EntryType::Link if entry.is_broken_link() => Some(self.broken_link),
+            EntryType::Link => Some(self.symlink),
+            EntryType::Fifo => Some(self.fifo),
+            EntryType::Socket => Some(self.socket),
+            EntryType::BlockDevice | EntryType::CharDevice => Some(self.device),
+            EntryType::File if entry.is_exec => Some(self.executable),
+            EntryType::File => Path::new(&entry.name)
+                .extension()
+                .and_then(|ext| self.extensions.get(&ext.to_string_lossy().to_lowercase()))
+                .copied(),
+        }
+    }
+}
+
+// Picks the style like `ls` does, from what the Explorer already knows about the entry -> Nothing is read from the disk.
+// The permission based indicators come first, then the file name patterns and then the type.
+fn ls_style(ls_colors: &LsColors, entry: &FileEntry) -> Option<Style> {
+    let mode = entry.mode.unwrap_or_default();
+    let (specific, base) = match entry.e_type {
+        EntryType::Dir => (
+            vec![
+                (mode & 0o1002 == 0o1002).then_some(Indicator::StickyAndOtherWritable),
+                (mode & 0o0002 != 0).then_some(Indicator::OtherWritable),
+                (mode & 0o1000 != 0).then_some(Indicator::Sticky),
+            ],
+            Indicator::Directory,
+        ),
+        EntryType::Link => (vec![entry.is_broken_link().then_some(Indicator::OrphanedSymbolicLink)], Indicator::SymbolicLink),
+        EntryType::Fifo => (vec![], Indicator::FIFO),
+        EntryType::Socket => (vec![], Indicator::Socket),
+        EntryType::BlockDevice => (vec![], Indicator::BlockDevice),
+        EntryType::CharDevice => (vec![], Indicator::CharacterDevice),
+        EntryType::File => (
+            vec![
+                (mode & 0o4000 != 0).then_some(Indicator::Setuid),
+                (mode & 0o2000 != 0).then_some(Indicator::Setgid),
+                entry.is_exec.then_some(Indicator::ExecutableFile),
+                entry.links.is_some_and(|links| links > 1).then_some(Indicator::MultipleHardLinks),
+            ],
+            Indicator::RegularFile,
+        ),
+    };
+    let style = specific.into_iter().flatten().find_map(|indicator| ls_colors.style_for_indicator(indicator));
+    let style = match style {
+        Some(style) => Some(style),
+        None if entry.e_type == EntryType::File => ls_colors.style_for_str(&entry.name).or_else(|| ls_colors.style_for_indicator(base)),
+        None => ls_colors.style_for_indicator(base),
+    };
+    style.map(convert_style)
+}
+
+fn convert_color(color: lscolors::Color) -> Color {
+    match color {
+        lscolors::Color::Black => Color::Black,
+        lscolors::Color::Red => Color::Red,
+        lscolors::Color::Green => Color::Green,
+        lscolors::Color::Yellow => Color::Yellow,
+        lscolors::Color::Blue => Color::Blue,
+        lscolors::Color::Magenta => Color::Magenta,
+        lscolors::Color::Cyan => Color::Cyan,
+        lscolors::Color::White => Color::Gray,
+        lscolors::Color::BrightBlack => Color::DarkGray,
+        lscolors::Color::BrightRed => Color::LightRed,
+        lscolors::Color::BrightGreen => Color::LightGreen,
+        lscolors::Color::BrightYellow => Color::LightYellow,
+        lscolors::Color::BrightBlue => Color::LightBlue,
+        lscolors::Color::BrightMagenta => Color::LightMagenta,
+        lscolors::Color::BrightCyan => Color::LightCyan,
+        lscolors::Color::BrightWhite => Color::White,
+        lscolors::Color::Fixed(index) => Color::Indexed(index),
+        lscolors::Color::RGB(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+fn convert_style(style: &lscolors::Style) -> Style {
+    let mut converted = Style::new();
+    if let Some(fg) = style.foreground {
+        converted = converted.fg(convert_color(fg));
+    }
+    if let Some(bg) = style.background {
+        converted = converted.bg(convert_color(bg));
+    }
+    let font = style.font_style;
+    for (set, modifier) in [
+        (font.bold, Modifier::BOLD),
+        (font.dimmed, Modifier::DIM),
+        (font.italic, Modifier::ITALIC),
+        (font.underline, Modifier::UNDERLINED),
+        (font.slow_blink, Modifier::SLOW_BLINK),
+        (font.rapid_blink, Modifier::RAPID_BLINK),
+        (font.reverse, Modifier::REVERSED),
+        (font.hidden, Modifier::HIDDEN),
+        (font.strikethrough, Modifier::CROSSED_OUT),
+    ] {
+        if set {
+            converted = converted.add_modifier(modifier);
+        }
+    }
+    converted
+}
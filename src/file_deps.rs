@@ -1,10 +1,15 @@
-use std::{fs, path::Path, vec::IntoIter};
+use std::{fs, path::Path, time::SystemTime, vec::IntoIter};
 use chrono::{DateTime, Local};
 use hf::is_hidden;
 use is_executable::IsExecutable;
 
-use crate::{explorer::{EntryType, FileEntry}, file_size_deps::find_length};
+use crate::{explorer::{EntryType, FileEntry, LinkTarget}, file_size_deps::{find_bytes, find_length}, owners::unix_owner, permissions::unix_mode};
 
+// Time as shown in the Modified At column
+pub fn format_time(time: SystemTime) -> String {
+    let date: DateTime<Local> = time.into();
+    format!("{}", date.format("%b %e %Y %H:%M"))
+}
 
 fn get_files(path: &Path, directory_size: bool, byte_size: bool) -> Vec<FileEntry> {
     let mut data = Vec::default();
@@ -35,53 +40,137 @@ pub fn get_data(path: &Path, all:bool, hiddenonly: bool, directory_size: bool, b
     Ok(get_files)
 }
 
+/// Type of an entry, from its metadata. A symlink is not followed by `symlink_metadata`.
+#[cfg(unix)]
+pub fn entry_type(file_type: fs::FileType) -> EntryType {
+    use std::os::unix::fs::FileTypeExt;
+    if file_type.is_dir() {
+        EntryType::Dir
+    } else if file_type.is_symlink() {
+        EntryType::Link
+    } else if file_type.is_fifo() {
+        EntryType::Fifo
+    } else if file_type.is_socket() {
+        EntryType::Socket
+    } else if file_type.is_block_device() {
+        EntryType::BlockDevice
+    } else if file_type.is_char_device() {
+        EntryType::CharDevice
+    } else {
+        EntryType::File
+    }
+}
+
+#[cfg(not(unix))]
+pub fn entry_type(file_type: fs::FileType) -> EntryType {
+    if file_type.is_dir() {
+        EntryType::Dir
+    } else if file_type.is_symlink() {
+        EntryType::Link
+    } else {
+        EntryType::File
+    }
+}
+
+/// (major, minor) number of a device
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn device_numbers(meta: &fs::Metadata) -> (u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    let rdev = meta.rdev() as libc::dev_t;
+    (libc::major(rdev) as u64, libc::minor(rdev) as u64)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn device_numbers(_meta: &fs::Metadata) -> (u64, u64) {
+    (0, 0)
+}
+
+/// (inode number, hard link count) of the entry, on Unix
+#[cfg(unix)]
+pub fn inode_links(meta: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.ino(), meta.nlink()))
+}
+
+#[cfg(not(unix))]
+pub fn inode_links(_meta: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+// Where the symlink points to. `None` when it isn't a symlink.
+fn link_target(path: &Path) -> Option<LinkTarget> {
+    let target = fs::read_link(path).ok()?;
+    let target_meta = fs::metadata(path);
+    Some(LinkTarget {
+        path: target,
+        is_dir: target_meta.as_ref().is_ok_and(|meta| meta.is_dir()),
+        broken: target_meta.is_err(),
+    })
+}
+
 // To collect data about directories and map them into a vector so that they can be displayed in table
 fn map_dir_data(file: fs::DirEntry, data: &mut Vec<FileEntry>, dir_index: &mut usize, directory_size: bool, byte_size: bool) -> fs::DirEntry {
-    if let Ok(meta) = fs::metadata(file.path()) {
-        if meta.is_dir() {
-            data.insert(*dir_index, FileEntry {
-                name: file
-                    .file_name()
-                    .into_string()
-                    .unwrap_or("unknown name".into()),
-                e_type: EntryType::Dir,
-                size: find_length(&file.path(), directory_size, byte_size),
-                modified_at: if let Ok(mod_time) = meta.modified() {
-                    let date: DateTime<Local> = mod_time.into();
-                    format!("{}", date.format("%b %e %Y %H:%M"))
-                } else {
-                    String::default()
-                },
-                hidden: is_hidden(file.path()).unwrap_or(false),
-                is_exec: file.path().is_executable(),
-            });
-            *dir_index += 1;
-        }
+    if let Ok(meta) = fs::symlink_metadata(file.path()) && meta.is_dir() {
+        data.insert(*dir_index, FileEntry {
+            name: file
+                .file_name()
+                .into_string()
+                .unwrap_or("unknown name".into()),
+            e_type: EntryType::Dir,
+            size: find_length(&file.path(), directory_size, byte_size),
+            size_bytes: find_bytes(&file.path(), directory_size),
+            modified: meta.modified().ok(),
+            modified_at: meta.modified().map(format_time).unwrap_or_default(),
+            created: meta.created().ok(),
+            accessed: meta.accessed().ok(),
+            hidden: is_hidden(file.path()).unwrap_or(false),
+            is_exec: file.path().is_executable(),
+            mode: unix_mode(&meta),
+            owner: unix_owner(&meta),
+            inode: inode_links(&meta).map(|(inode, _links)| inode),
+            links: inode_links(&meta).map(|(_inode, links)| links),
+            link: None,
+        });
+        *dir_index += 1;
     }
     file
 }
 
-// To collect data about files and map them into a vector so that they can be displayed in table
+// To collect data about files and symlinks and map them into a vector so that they can be displayed in table.
+// A symlink is described by itself, not by its target.
 fn map_file_data(file: fs::DirEntry, data: &mut Vec<FileEntry>, byte_size: bool) {
-    if let Ok(meta) = fs::metadata(file.path()) {
-        if !meta.is_dir() {
-            data.push(FileEntry {
-                name: file
-                    .file_name()
-                    .into_string()
-                    .unwrap_or("unknown name".into()),
-                e_type: EntryType::File,
-                size: find_length(&file.path(), false, byte_size),
-                modified_at: if let Ok(mod_time) = meta.modified() {
-                    let date: DateTime<Local> = mod_time.into();
-                    format!("{}", date.format("%b %e %Y %H:%M"))
-                } else {
-                    String::default()
-                },
-                hidden: is_hidden(file.path()).unwrap_or(false),
-                is_exec: file.path().is_executable(),
-            });
-        }
+    if let Ok(meta) = fs::symlink_metadata(file.path()) && !meta.is_dir() {
+        let e_type = entry_type(meta.file_type());
+        let link = if meta.is_symlink() { link_target(&file.path()) } else { None };
+        // Devices show their numbers like `ls -l`, the length of the others means nothing
+        let size = match e_type {
+            EntryType::BlockDevice | EntryType::CharDevice => {
+                let (major, minor) = device_numbers(&meta);
+                format!("{major}, {minor}")
+            },
+            EntryType::Fifo | EntryType::Socket => String::from("-"),
+            _ => find_length(&file.path(), false, byte_size),
+        };
+        data.push(FileEntry {
+            name: file
+                .file_name()
+                .into_string()
+                .unwrap_or("unknown name".into()),
+            size_bytes: if e_type.is_special() { 0 } else { meta.len() },
+            e_type,
+            size,
+            modified: meta.modified().ok(),
+            modified_at: meta.modified().map(format_time).unwrap_or_default(),
+            created: meta.created().ok(),
+            accessed: meta.accessed().ok(),
+            hidden: is_hidden(file.path()).unwrap_or(false),
+            is_exec: file.path().is_executable(),
+            mode: unix_mode(&meta),
+            owner: unix_owner(&meta),
+            inode: inode_links(&meta).map(|(inode, _links)| inode),
+            links: inode_links(&meta).map(|(_inode, links)| links),
+            link,
+        });
     }
 }
 
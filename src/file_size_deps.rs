@@ -47,6 +47,17 @@ pub fn convert(num: f64) -> String {
   format!("{}{} {}", negative, pretty_bytes, unit)
 }
 
+// Size in bytes used for sorting -> Directories are 0 unless their size is calculated
+pub fn find_bytes(path: &Path, directory_size: bool) -> u64 {
+    match path.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => {
+            if directory_size { get_size(path).unwrap_or(0_u64) } else { 0 }
+        },
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
 pub fn find_length(path: &Path, directory_size: bool, byte_size: bool) -> String {
     if let Ok(metadata) = path.symlink_metadata() {
         let mut bytes: u64 = metadata.len();
@@ -0,0 +1,113 @@
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use strum::{EnumIter, IntoEnumIterator};
+use tui_input::Input;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum FilterMode {
+    #[default]
+    Substring,
+    Glob,
+    Regex,
+}
+
+impl FilterMode {
+    /// To cycle through the filter modes -> Used to handle `TAB` Keyevent while typing a filter
+    pub fn next(&self) -> Self {
+        let variants: Vec<FilterMode> = FilterMode::iter().collect();
+        let current_index = variants.iter().position(|&v| v == *self).unwrap_or(0);
+        variants[(current_index + 1) % variants.len()]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FilterMode::Substring => "substring",
+            FilterMode::Glob => "glob",
+            FilterMode::Regex => "regex",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Substring(String),
+    Glob(Pattern),
+    Regex(Regex),
+    Invalid,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// Pattern typed after `/`
+    pub input: Input,
+    pub mode: FilterMode,
+    /// The pattern is being typed in
+    pub editing: bool,
+    /// The listing is narrowed down to the matching entries
+    pub applied: bool,
+    matcher: Option<Matcher>,
+}
+
+impl Filter {
+    pub fn pattern(&self) -> &str {
+        self.input.value()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pattern().is_empty()
+    }
+
+    pub fn is_invalid(&self) -> bool {
+        matches!(self.matcher, Some(Matcher::Invalid))
+    }
+
+    /// Compiles the current pattern -> Has to be called whenever the pattern or the mode changes
+    pub fn update(&mut self) {
+        let pattern = self.pattern();
+        self.matcher = if pattern.is_empty() {
+            None
+        } else {
+            Some(match self.mode {
+                FilterMode::Substring => Matcher::Substring(pattern.to_lowercase()),
+                FilterMode::Glob => Pattern::new(pattern).map(Matcher::Glob).unwrap_or(Matcher::Invalid),
+                FilterMode::Regex => Regex::new(pattern).map(Matcher::Regex).unwrap_or(Matcher::Invalid),
+            })
+        };
+    }
+
+    pub fn clear(&mut self) {
+        self.input.reset();
+        self.editing = false;
+        self.applied = false;
+        self.matcher = None;
+    }
+
+    /// Byte range of the part of `name` that matched the pattern
+    pub fn match_range(&self, name: &str) -> Option<(usize, usize)> {
+        match self.matcher.as_ref()? {
+            Matcher::Substring(needle) => {
+                let haystack = name.to_lowercase();
+                let start = haystack.find(needle.as_str())?;
+                // Lowercasing can change the byte length of some characters. Highlight the whole name then.
+                if haystack.len() == name.len() {
+                    Some((start, start + needle.len()))
+                } else {
+                    Some((0, name.len()))
+                }
+            },
+            Matcher::Glob(pattern) => {
+                let options = MatchOptions {
+                    case_sensitive: false,
+                    ..MatchOptions::new()
+                };
+                pattern.matches_with(name, options).then_some((0, name.len()))
+            },
+            Matcher::Regex(regex) => regex.find(name).map(|found| (found.start(), found.end())),
+            Matcher::Invalid => None,
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.match_range(name).is_some()
+    }
+}
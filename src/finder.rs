@@ -0,0 +1,417 @@
+use std::{
+    iter,
+    path::{PathBuf, is_separator},
+    sync::{Arc, atomic::{AtomicBool, AtomicU64, Ordering}, mpsc::{self, Receiver, Sender}},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, Cell, Clear, HighlightSpacing, Paragraph, Row, Table, TableState},
+};
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use crate::{App, AppEvent, color_theme::ColorTheme, dependencies::HandlesInput, quick_access::update_qa_files, walker::walk_tree};
+
+/// Number of best matches kept for display
+const MAX_RESULTS: usize = 500;
+/// Number of paths the walker collects before handing them over to the ranker
+const BATCH_SIZE: usize = 4096;
+/// Candidates ranked between two checks for a newer query
+const CHECK_EVERY: usize = 4096;
+
+/// The best matches of a query, ranked on the finder thread
+#[derive(Debug, Default)]
+pub struct FinderResults {
+    /// The lowercase query ranked
+    pub query: String,
+    /// Paths relative to the root, best first. Directories end with a `/`.
+    pub paths: Vec<String>,
+    /// How many of the candidates match the query
+    pub matched: usize,
+    /// How many candidates were found so far
+    pub total: usize,
+    pub walking: bool,
+}
+
+pub struct Finder {
+    pub input: Input,
+    pub root: PathBuf,
+    /// Best matches shown in the popup, for `results.query`
+    results: FinderResults,
+    /// The last query sent to the ranker
+    last_query: String,
+    pub state: TableState,
+    /// Bumped by every walk and every query. Results ranked for an older one are dropped.
+    generation: u64,
+    /// The generation of the latest query, shared with the ranker so that it gives up on the older ones
+    latest: Arc<AtomicU64>,
+    cancel: Arc<AtomicBool>,
+    /// Sends the queries to the ranker of the current walk
+    ranker_tx: Option<Sender<RankerMsg>>,
+    pub color_theme: ColorTheme,
+}
+
+enum RankerMsg {
+    Batch(Vec<String>),
+    Query(u64, String),
+    WalkDone,
+}
+
+// Keeps the candidates found by the walker and ranks them for the query, off the UI thread
+struct Ranker {
+    /// Paths relative to the root. Directories end with a `/`.
+    candidates: Vec<String>,
+    /// Indices of all the candidates matching the query
+    matches: Vec<usize>,
+    /// Best matches as (score, candidate index)
+    results: Vec<(i64, usize)>,
+    query: String,
+    generation: u64,
+    latest: Arc<AtomicU64>,
+    walking: bool,
+}
+
+/// Scores how well the lowercase `pattern` fuzzy matches `candidate`. Higher is better.
+/// The byte positions of the matched characters are pushed into `positions` if given.
+pub fn fuzzy_score(pattern: &[char], candidate: &str, mut positions: Option<&mut Vec<usize>>) -> Option<i64> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let basename_start = candidate
+        .trim_end_matches(is_separator)
+        .rfind(is_separator)
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    let mut score: i64 = 0;
+    let mut pattern_idx = 0;
+    let mut prev_match: Option<usize> = None;
+    let mut prev_char: Option<char> = None;
+    for (char_idx, (byte_idx, c)) in candidate.char_indices().enumerate() {
+        if pattern_idx == pattern.len() {
+            break;
+        }
+        if c.to_lowercase().next() == Some(pattern[pattern_idx]) {
+            score += 16;
+            match prev_match {
+                // Consecutive characters
+                Some(prev) if prev + 1 == char_idx => score += 24,
+                Some(prev) => score -= (char_idx - prev - 1).min(8) as i64,
+                None => {}
+            }
+            // Start of a word
+            let at_boundary = prev_char.is_none_or(|p| is_separator(p) || matches!(p, '_' | '-' | '.' | ' '))
+                || (prev_char.is_some_and(char::is_lowercase) && c.is_uppercase());
+            if at_boundary {
+                score += 20;
+            }
+            if byte_idx >= basename_start {
+                score += 8;
+            }
+            if let Some(found) = positions.as_deref_mut() {
+                found.push(byte_idx);
+            }
+            prev_match = Some(char_idx);
+            pattern_idx += 1;
+        }
+        prev_char = Some(c);
+    }
+    if pattern_idx < pattern.len() {
+        return None;
+    }
+    // Shorter paths win among equally good matches
+    Some(score - candidate.len() as i64 / 4)
+}
+
+// Keeps the best `MAX_RESULTS`, best first. Only those are sorted, however many matched.
+fn sort_results(results: &mut Vec<(i64, usize)>) {
+    let best_first = |a: &(i64, usize), b: &(i64, usize)| b.0.cmp(&a.0).then(a.1.cmp(&b.1));
+    if results.len() > MAX_RESULTS {
+        results.select_nth_unstable_by(MAX_RESULTS, best_first);
+        results.truncate(MAX_RESULTS);
+    }
+    results.sort_by(best_first);
+}
+
+impl Ranker {
+    // Scores the candidates from `start` on for the query
+    fn rank_new(&mut self, start: usize) {
+        let pattern: Vec<char> = self.query.chars().collect();
+        for idx in start..self.candidates.len() {
+            if let Some(score) = fuzzy_score(&pattern, &self.candidates[idx], None) {
+                self.matches.push(idx);
+                if self.results.len() < MAX_RESULTS || pattern.is_empty() || score > self.results[self.results.len() - 1].0 {
+                    self.results.push((score, idx));
+                }
+            }
+        }
+        sort_results(&mut self.results);
+    }
+
+    // Re-ranks the candidates for a new query. Gives up when a newer one was typed meanwhile -> Returns false.
+    fn set_query(&mut self, query: String) -> bool {
+        let pattern: Vec<char> = query.chars().collect();
+        // A longer query can only match a subset of what the shorter one matched
+        let pool: Vec<usize> = if !self.query.is_empty() && query.starts_with(&self.query) {
+            std::mem::take(&mut self.matches)
+        } else {
+            (0..self.candidates.len()).collect()
+        };
+        self.matches.clear();
+        self.results.clear();
+        for (count, idx) in pool.into_iter().enumerate() {
+            if count % CHECK_EVERY == 0 && self.latest.load(Ordering::Relaxed) != self.generation {
+                // The matches are incomplete, the next query starts over from all the candidates
+                self.query.clear();
+                return false;
+            }
+            if let Some(score) = fuzzy_score(&pattern, &self.candidates[idx], None) {
+                self.matches.push(idx);
+                self.results.push((score, idx));
+                if self.results.len() >= 4 * MAX_RESULTS {
+                    sort_results(&mut self.results);
+                }
+            }
+        }
+        sort_results(&mut self.results);
+        self.query = query;
+        true
+    }
+
+    fn results(&self) -> FinderResults {
+        FinderResults {
+            query: self.query.clone(),
+            paths: self.results.iter().map(|(_score, idx)| self.candidates[*idx].clone()).collect(),
+            matched: self.matches.len(),
+            total: self.candidates.len(),
+            walking: self.walking,
+        }
+    }
+}
+
+// Runs on the finder thread until the walk is over and the Finder has moved on. Whatever piled up while ranking
+// is taken at once, so only the latest query is ranked.
+fn ranker_worker(generation: u64, latest: Arc<AtomicU64>, rx: Receiver<RankerMsg>, tx: Sender<AppEvent>, cancel: Arc<AtomicBool>) {
+    let mut ranker = Ranker { candidates: vec![], matches: vec![], results: vec![], query: String::new(), generation, latest, walking: true };
+    while let Ok(first) = rx.recv() {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let start = ranker.candidates.len();
+        let mut new_query: Option<(u64, String)> = None;
+        for msg in iter::once(first).chain(rx.try_iter()) {
+            match msg {
+                RankerMsg::Batch(batch) => ranker.candidates.extend(batch),
+                RankerMsg::Query(generation, query) => new_query = Some((generation, query)),
+                RankerMsg::WalkDone => ranker.walking = false,
+            }
+        }
+        ranker.rank_new(start);
+        if let Some((generation, query)) = new_query {
+            ranker.generation = generation;
+            if !ranker.set_query(query) {
+                continue;
+            }
+        }
+        if tx.send(AppEvent::FinderResults(ranker.generation, ranker.results())).is_err() {
+            return;
+        }
+    }
+}
+
+impl Finder {
+    pub fn new(read_color_theme: ColorTheme) -> Finder {
+        Finder {
+            input: Input::default(),
+            root: PathBuf::default(),
+            results: FinderResults::default(),
+            last_query: String::default(),
+            state: TableState::default(),
+            generation: 0,
+            latest: Arc::new(AtomicU64::new(0)),
+            cancel: Arc::new(AtomicBool::new(false)),
+            ranker_tx: None,
+            color_theme: read_color_theme,
+        }
+    }
+
+    /// Starts walking the tree under `root` on a background thread. The paths found are ranked on another one,
+    /// which sends the best matches as `AppEvent::FinderResults`.
+    pub fn open(&mut self, root: PathBuf, include_hidden: bool, tx: Sender<AppEvent>) {
+        self.close();
+        self.generation += 1;
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.root = root.clone();
+        self.input.reset();
+        self.last_query.clear();
+        self.results = FinderResults { walking: true, ..FinderResults::default() };
+        self.state.select(None);
+
+        let (ranker_tx, ranker_rx) = mpsc::channel::<RankerMsg>();
+        self.ranker_tx = Some(ranker_tx.clone());
+        let generation = self.generation;
+        self.latest = Arc::new(AtomicU64::new(generation));
+        let latest = Arc::clone(&self.latest);
+        let ranker_cancel = Arc::clone(&self.cancel);
+        thread::spawn(move || ranker_worker(generation, latest, ranker_rx, tx, ranker_cancel));
+
+        let cancel = Arc::clone(&self.cancel);
+        thread::spawn(move || {
+            let mut batch: Vec<String> = vec![];
+            let mut last_sent = Instant::now();
+            walk_tree(&root, include_hidden, &cancel, |path, is_dir| {
+                if let Ok(relative) = path.strip_prefix(&root) {
+                    let mut display = relative.to_string_lossy().to_string();
+                    if is_dir {
+                        display.push('/');
+                    }
+                    batch.push(display);
+                }
+                if batch.len() >= BATCH_SIZE || last_sent.elapsed() >= Duration::from_millis(100) {
+                    if ranker_tx.send(RankerMsg::Batch(std::mem::take(&mut batch))).is_err() {
+                        cancel.store(true, Ordering::Relaxed);
+                    }
+                    last_sent = Instant::now();
+                }
+            });
+            if !batch.is_empty() {
+                let _ = ranker_tx.send(RankerMsg::Batch(batch));
+            }
+            let _ = ranker_tx.send(RankerMsg::WalkDone);
+        });
+    }
+
+    /// Stops the walk and the ranking, if they are going on
+    pub fn close(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.ranker_tx = None;
+        self.results.walking = false;
+    }
+
+    pub fn set_results(&mut self, generation: u64, results: FinderResults) {
+        if generation != self.generation {
+            return;
+        }
+        let new_query = results.query != self.results.query;
+        self.results = results;
+        if new_query || self.state.selected().is_none_or(|idx| idx >= self.results.paths.len()) {
+            self.state.select(if self.results.paths.is_empty() { None } else { Some(0) });
+        }
+    }
+
+    // Hands the current query over to the ranker
+    fn send_query(&mut self) {
+        self.generation += 1;
+        self.latest.store(self.generation, Ordering::Relaxed);
+        self.last_query = self.input.value().to_lowercase();
+        if let Some(ranker_tx) = &self.ranker_tx {
+            let _ = ranker_tx.send(RankerMsg::Query(self.generation, self.last_query.clone()));
+        }
+    }
+
+    /// Absolute path of the selected result
+    pub fn selected(&self) -> Option<PathBuf> {
+        let path = self.results.paths.get(self.state.selected()?)?;
+        Some(self.root.join(path.trim_end_matches('/')))
+    }
+
+    fn next_row(&mut self) {
+        if !self.results.paths.is_empty() {
+            let i = self.state.selected().map_or(0, |i| (i + 1) % self.results.paths.len());
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous_row(&mut self) {
+        if !self.results.paths.is_empty() {
+            let i = self.state.selected().map_or(0, |i| if i == 0 { self.results.paths.len() - 1 } else { i - 1 });
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let status = format!(
+            " {}/{} {}",
+            self.results.matched,
+            self.results.total,
+            if self.results.walking { "(searching...) " } else { "" }
+        );
+        let block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title(" Find Files ")
+            .title_bottom(status)
+            .title_style(Style::new().fg(self.color_theme.primary))
+            .border_style(Style::new().fg(self.color_theme.border));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let [input_area, results_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+
+        frame.render_widget(Paragraph::new(format!(" > {}", self.input.value())), input_area);
+        frame.set_cursor_position((input_area.x + 3 + self.input.visual_cursor() as u16, input_area.y));
+
+        let pattern: Vec<char> = self.results.query.chars().collect();
+        let highlight_style = Style::default().fg(self.color_theme.border).add_modifier(Modifier::BOLD);
+        let rows = self.results.paths.iter().map(|candidate| {
+            let mut positions = vec![];
+            fuzzy_score(&pattern, candidate, Some(&mut positions));
+            let spans: Vec<Span> = candidate
+                .char_indices()
+                .map(|(byte_idx, c)| {
+                    if positions.contains(&byte_idx) {
+                        Span::styled(c.to_string(), highlight_style)
+                    } else {
+                        Span::raw(c.to_string())
+                    }
+                })
+                .collect();
+            Row::new([Cell::from(Line::from(spans))]).style(Style::new().fg(self.color_theme.primary))
+        });
+        let t = Table::new(rows, [Constraint::Min(10)])
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED).fg(self.color_theme.selector))
+            .highlight_symbol(Text::from(vec![" ▶ ".into()]))
+            .bg(Color::Black)
+            .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(t, results_area, &mut self.state);
+    }
+}
+
+/// Moves the Explorer to the parent of the selected result and selects it
+pub fn finder_handle_enter(app: &mut App) {
+    if let Some(picked) = app.finder.selected() {
+        let dir_path = PathBuf::from(picked.parent().unwrap_or(&app.finder.root));
+        app.navigate_to(dir_path.clone());
+        if let Some(file_name) = picked.file_name() {
+            app.explorer.select_name(&file_name.to_string_lossy());
+        }
+        update_qa_files(app, String::from(dir_path.file_name().and_then(|name| name.to_str()).unwrap_or("default")), dir_path.clone());
+    }
+    app.finder.close();
+    app.finder_shown = false;
+}
+
+impl HandlesInput for Finder {
+    fn handle_input(&mut self, event: Event) -> Result<(), String> {
+        if let Event::Key(key_event) = event && key_event.kind == KeyEventKind::Press {
+            let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+            match key_event.code {
+                KeyCode::Down => self.next_row(),
+                KeyCode::Up => self.previous_row(),
+                KeyCode::Char('n') | KeyCode::Char('j') if ctrl => self.next_row(),
+                KeyCode::Char('p') | KeyCode::Char('k') if ctrl => self.previous_row(),
+                _ => {
+                    self.input.handle_event(&Event::Key(key_event));
+                    if self.input.value().to_lowercase() != self.last_query {
+                        self.send_query();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, atomic::{AtomicBool, AtomicUsize, Ordering}, mpsc::{self, Sender}},
+    thread,
+};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, Cell, Clear, HighlightSpacing, Row, Table, TableState},
+};
+use regex::{Regex, RegexBuilder};
+
+use crate::{App, AppEvent, color_theme::ColorTheme, dependencies::{HandlesInput, is_binary}, open_files::handle_file_open, quick_access::update_qa_files, walker::walk_tree};
+
+/// The search stops after this many matching lines
+const MAX_HITS: usize = 10_000;
+/// Longest snippet of a matching line that is kept
+const MAX_SNIPPET: usize = 300;
+
+#[derive(Debug, Clone)]
+pub struct GrepHit {
+    pub path: PathBuf,
+    pub line: usize,
+    pub snippet: String,
+    /// Byte range of the match in the snippet
+    pub range: (usize, usize),
+}
+
+pub struct GrepSearch {
+    pub pattern: String,
+    pub root: PathBuf,
+    pub hits: Vec<GrepHit>,
+    pub state: TableState,
+    pub searching: bool,
+    generation: u64,
+    cancel: Arc<AtomicBool>,
+    pub color_theme: ColorTheme,
+}
+
+// Searches a single file and returns the matching lines. Binary and unreadable files give no hits.
+fn search_file(path: &Path, regex: &Regex, cancel: &AtomicBool) -> Vec<GrepHit> {
+    let mut hits = vec![];
+    // Only regular files are opened. Opening a FIFO or a device could block forever.
+    if !path.symlink_metadata().is_ok_and(|meta| meta.is_file()) {
+        return hits;
+    }
+    let Ok(file) = File::open(path) else {
+        return hits;
+    };
+    let mut reader = BufReader::new(file);
+    let mut head = vec![0_u8; 8192];
+    let Ok(head_len) = reader.by_ref().take(8192).read(&mut head) else {
+        return hits;
+    };
+    if is_binary(&head[..head_len]) {
+        return hits;
+    }
+    let mut reader = (&head[..head_len]).chain(reader);
+    let mut buf: Vec<u8> = vec![];
+    let mut line_no = 0;
+    while let Ok(read) = reader.read_until(b'\n', &mut buf) {
+        if read == 0 || cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        line_no += 1;
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches(['\n', '\r']);
+        if let Some(found) = regex.find(line) {
+            // Keeping the snippet around the match for long lines
+            let mut start = found.start().saturating_sub(MAX_SNIPPET / 2);
+            while !line.is_char_boundary(start) {
+                start -= 1;
+            }
+            let mut end = (start + MAX_SNIPPET).min(line.len()).max(found.end());
+            while !line.is_char_boundary(end) {
+                end += 1;
+            }
+            hits.push(GrepHit {
+                path: path.to_path_buf(),
+                line: line_no,
+                snippet: line[start..end].to_string(),
+                range: (found.start() - start, found.end() - start),
+            });
+        }
+        buf.clear();
+    }
+    hits
+}
+
+impl GrepSearch {
+    pub fn new(read_color_theme: ColorTheme) -> GrepSearch {
+        GrepSearch {
+            pattern: String::default(),
+            root: PathBuf::default(),
+            hits: vec![],
+            state: TableState::default(),
+            searching: false,
+            generation: 0,
+            cancel: Arc::new(AtomicBool::new(false)),
+            color_theme: read_color_theme,
+        }
+    }
+
+    /// Searches the files under `root` on background threads. The hits are sent as `AppEvent::GrepBatch`.
+    pub fn start(&mut self, pattern: &str, ignore_case: bool, root: PathBuf, include_hidden: bool, tx: Sender<AppEvent>) -> Result<(), String> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|err| format!("Invalid search pattern: {err}"))?;
+        self.stop();
+        self.generation += 1;
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.pattern = String::from(pattern);
+        self.root = root.clone();
+        self.hits.clear();
+        self.state.select(None);
+        self.searching = true;
+
+        let generation = self.generation;
+        let cancel = Arc::clone(&self.cancel);
+        thread::spawn(move || {
+            let (path_tx, path_rx) = mpsc::channel::<PathBuf>();
+            let path_rx = Arc::new(Mutex::new(path_rx));
+            let hit_count = Arc::new(AtomicUsize::new(0));
+            let workers: Vec<_> = (0..thread::available_parallelism().map_or(4, |n| n.get()))
+                .map(|_| {
+                    let path_rx = Arc::clone(&path_rx);
+                    let regex = regex.clone();
+                    let cancel = Arc::clone(&cancel);
+                    let hit_count = Arc::clone(&hit_count);
+                    let tx = tx.clone();
+                    thread::spawn(move || {
+                        loop {
+                            let next_path = path_rx.lock().map(|rx| rx.recv());
+                            let Ok(Ok(path)) = next_path else {
+                                break;
+                            };
+                            if cancel.load(Ordering::Relaxed) {
+                                break;
+                            }
+                            let hits = search_file(&path, &regex, &cancel);
+                            if !hits.is_empty() {
+                                if hit_count.fetch_add(hits.len(), Ordering::Relaxed) + hits.len() >= MAX_HITS {
+                                    cancel.store(true, Ordering::Relaxed);
+                                }
+                                if tx.send(AppEvent::GrepBatch(generation, hits)).is_err() {
+                                    cancel.store(true, Ordering::Relaxed);
+                                }
+                            }
+                        }
+                    })
+                })
+                .collect();
+            walk_tree(&root, include_hidden, &cancel, |path, is_dir| {
+                if !is_dir {
+                    let _ = path_tx.send(path.to_path_buf());
+                }
+            });
+            drop(path_tx);
+            for worker in workers {
+                let _ = worker.join();
+            }
+            let _ = tx.send(AppEvent::GrepDone(generation));
+        });
+        Ok(())
+    }
+
+    /// Stops the search, if one is going on
+    pub fn stop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.searching = false;
+    }
+
+    pub fn add_hits(&mut self, generation: u64, hits: Vec<GrepHit>) {
+        if generation != self.generation {
+            return;
+        }
+        self.hits.extend(hits);
+        self.hits.truncate(MAX_HITS);
+        if self.state.selected().is_none() && !self.hits.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn finish(&mut self, generation: u64) {
+        if generation == self.generation {
+            self.searching = false;
+        }
+    }
+
+    pub fn selected(&self) -> Option<&GrepHit> {
+        self.hits.get(self.state.selected()?)
+    }
+
+    fn next_row(&mut self) {
+        if !self.hits.is_empty() {
+            let i = self.state.selected().map_or(0, |i| (i + 1) % self.hits.len());
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous_row(&mut self) {
+        if !self.hits.is_empty() {
+            let i = self.state.selected().map_or(0, |i| if i == 0 { self.hits.len() - 1 } else { i - 1 });
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let status = format!(
+            " {} matches {}",
+            self.hits.len(),
+            if self.searching { "(searching...) " } else { "" }
+        );
+        let instructions = Line::from(vec![
+            " <Enter>".blue().bold(),
+            " Open ".into(),
+            "<e>".blue().bold(),
+            " Show in Explorer ".into(),
+            "<Esc>".blue().bold(),
+            " Close ".into(),
+        ]).right_aligned();
+        let highlight_style = Style::default().fg(self.color_theme.border).add_modifier(Modifier::BOLD);
+        let location_style = Style::default().fg(self.color_theme.header);
+        let rows = self.hits.iter().map(|hit| {
+            let relative = hit.path.strip_prefix(&self.root).unwrap_or(&hit.path);
+            let (start, end) = hit.range;
+            Row::new([Cell::from(Line::from(vec![
+                Span::styled(format!("{}:{}: ", relative.to_string_lossy(), hit.line), location_style),
+                Span::raw(hit.snippet[..start].to_string()),
+                Span::styled(hit.snippet[start..end].to_string(), highlight_style),
+                Span::raw(hit.snippet[end..].to_string()),
+            ]))])
+            .style(Style::new().fg(self.color_theme.primary))
+        });
+        let t = Table::new(rows, [Constraint::Min(10)])
+            .block(
+                Block::bordered()
+                    .border_type(ratatui::widgets::BorderType::Rounded)
+                    .title(format!(" grep: {} ", self.pattern))
+                    .title_bottom(status)
+                    .title_bottom(instructions)
+                    .title_style(Style::new().fg(self.color_theme.primary))
+                    .border_style(Style::new().fg(self.color_theme.border)),
+            )
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED).fg(self.color_theme.selector))
+            .highlight_symbol(Text::from(vec![" ▶ ".into()]))
+            .bg(Color::Black)
+            .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(t, area, &mut self.state);
+    }
+}
+
+/// Opens the file of the selected hit with the commands in file_options.toml
+pub fn grep_handle_enter(app: &mut App) -> Result<(), String> {
+    if let Some(hit) = app.grep.selected() {
+        handle_file_open(&hit.path, &app.explorer.file_open_options)?;
+    }
+    Ok(())
+}
+
+/// Moves the Explorer to the directory of the selected hit and selects the file
+pub fn grep_reveal(app: &mut App) {
+    if let Some(hit) = app.grep.selected() {
+        let file_path = hit.path.clone();
+        let dir_path = PathBuf::from(file_path.parent().unwrap_or(&app.grep.root));
+        app.navigate_to(dir_path.clone());
+        if let Some(file_name) = file_path.file_name() {
+            app.explorer.select_name(&file_name.to_string_lossy());
+        }
+        update_qa_files(app, String::from(dir_path.file_name().and_then(|name| name.to_str()).unwrap_or("default")), dir_path.clone());
+        app.grep.stop();
+        app.grep_shown = false;
+    }
+}
+
+impl HandlesInput for GrepSearch {
+    fn handle_input(&mut self, event: Event) -> Result<(), String> {
+        if let Event::Key(key_event) = event && key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => self.next_row(),
+                KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
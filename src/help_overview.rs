@@ -31,20 +31,63 @@ impl HelpOverview {
             \th ->      Help Overview
             \ta ->      Input mode in the Path Field
             \t: ->      Input mode in Command bar
-            \tTab ->    Used to cycle through the explorers and input fields
+            \tf ->      Find files and directories under the Explorer's path
+            \tp ->      Show / Hide the Preview pane
+            \tH | Alt + Left ->     Go back to the previous location
+            \tL | Alt + Right ->    Go forward to the next location
+            \tR ->      List the recent locations
+            \tt ->      Open a new tab on the current directory
+            \tT ->      Close the current tab
+            \t] | [ ->  Switch to the next / previous tab
+            \tw ->      Turn the dual-pane mode on / off
+            \tJ ->      Show the Jobs panel (copy / move / delete running in the background)
+            \tz ->      Cycle the directory sizes shown: total size / item count / off
+            \tu ->      Undo the last file operation
+            \tCtrl + r -> Redo the last undone file operation
+            \tTab ->    Used to cycle through the explorers and input fields (left pane, then right pane in the dual-pane mode)
             \tq ->      Exits the app
+
+            Mouse
+            -----
+            \tClick ->         Focus the panel and select the row clicked
+            \tDouble click ->  Same as Enter on the row clicked
+            \tWheel ->         Scroll the list under the mouse
+            \tDrag ->          Drag the scrollbar of the Explorer
                 
             Explorer
             --------
             \tj | k ->      Up / Down selection
             \tr ->          Refresh the explorer
-            \tc ->          Copy the selected file/directory
-            \tv ->          Paste the copied file/directory
-            \tx ->          Cut the selected file/directory
-            \tdelete ->     Delete the selected item
+            \tc ->          Copy the selected (or marked) files/directories
+            \tC ->          Copy the selected (or marked) items, symlinks being pasted as what they point to
+            \tv ->          Paste the copied files/directories (also in another tab)
+            \tx ->          Cut the selected (or marked) files/directories
+            \tF5 ->         Copy the selected (or marked) items to the other pane
+            \tF6 ->         Move the selected (or marked) items to the other pane
+            \tdelete ->     Move the selected (or marked) items to Trash
+            \tShift + delete -> Permanently delete the selected (or marked) items (can't be undone)
+            \tSpace ->      Mark / Unmark the selected item
+            \tA ->          Mark all items in the directory
+            \tI ->          Invert the marks in the directory
+            \tEsc ->        Close the filter / Clear the search pattern / Clear all marks
+            \t/ ->          Filter the directory as you type (Tab switches between substring, glob and regex)
+            \t\tEnter ->      Keep the filter applied
+            \t\tEsc ->        Bring back the full listing
+            \tn | N ->      Jump to the next / previous match of the filter pattern
+            \ts ->          Cycle the sort column (Name / Extension / Size / Modified At)
+            \tS ->          Reverse the sort order
+            \tD ->          Toggle listing directories first
+            \tE ->          Rename the selected (or marked) items in $EDITOR, one name per line
+            \tM ->          Show / Hide the Mode column
+            \tP ->          Change the permissions of the selected item
+            \tO ->          Show / Hide the Owner and Group columns
+            \tV ->          Choose the columns, their order and widths (saved in settings.toml)
+            \tg ->          Jump to the target of the selected symlink
             \tEnter :-
-            \t\tOn File ->      Opens the file if the file extension is supported and defined in file_options.toml configuration file.
+            \t\tOn File ->      Opens the file with the first rule of file_options.toml matching it.
             \t\tOn Directory -> Navigates into the directory.
+            \t\tOn Symlink ->   Same as on what it points to
+            \t\tOn FIFO, socket or device -> Nothing is opened
                     
             Path Field
             ----------
@@ -74,6 +117,76 @@ impl HelpOverview {
             \tCreate a new file:        n <FILENAME>
             \tCreate a new directory:   b <DIRECTORY NAME>
             \tRename Operation:         r <OLD_EXISTING_NAME> <NEW_NAME>
+            \tRename with a pattern:    rename <FIND> <REPLACE> [upper|lower|title]
+            \t                          ($1 capture groups, {n} {n:03} {ext} {date} {date:%Y%m%d} tokens)
+            \tSort the Explorer:        sort <name|ext|size|modified> [asc|desc] [dirs]
+            \tSearch file contents:     grep [-i] <PATTERN>
+            \tChange the owner:         chown [-R] <USER>[:<GROUP>]
+            \tCreate a link:            ln [-s] [-r] <TARGET> [LINK_NAME]
+            \tBrowse the disk usage:    du [--cross-fs] [PATH]
+
+            File Finder
+            -----------
+            \tType ->               Fuzzy search the paths under the Explorer's path
+            \tUp | Down ->          Up / Down selection (also Ctrl + n / Ctrl + p)
+            \tEnter ->              Go to the directory of the selected result and select it
+            \tEsc ->                Close the File Finder
+
+            Name Conflicts (paste, move and rename)
+            ---------------------------------------
+            \to | O ->      Overwrite (O for all the remaining conflicts)
+            \ts | S ->      Skip
+            \tr | R ->      Keep both, with a numbered name like `name (2).ext`
+            \tn | N ->      Overwrite if newer
+            \tEsc ->        Cancel the operation
+
+            Jobs
+            ----
+            \tj | k ->      Up / Down selection
+            \tc ->          Cancel the selected job
+            \tC ->          Clear the finished jobs
+            \tJ | K ->      Scroll the failures of the selected job
+            \tEsc | q ->    Close the Jobs panel
+
+            Recent Locations
+            ----------------
+            \tj | k ->              Up / Down selection
+            \tEnter ->              Go to the selected location
+            \tEsc | q ->            Close Recent Locations
+
+            Search Results (grep)
+            ---------------------
+            \tj | k ->              Up / Down selection
+            \tEnter ->              Open the file of the selected result
+            \te ->                  Show the file in the Explorer
+            \tEsc | q ->            Close the Search Results
+
+            Disk Usage
+            ----------
+            \tj | k ->              Up / Down selection
+            \tEnter | l ->          Open the selected directory
+            \tBackspace | h ->      Go up to the parent directory
+            \ta ->                  Switch between the on-disk and apparent sizes
+            \td ->                  Move the selected entry to the trash (y to confirm)
+            \tEsc | q ->            Close the Disk Usage view
+
+            Permissions
+            -----------
+            \th | j | k | l -> Move between the checkboxes
+            \tSpace | x ->      Toggle the checkbox
+            \t0 - 7 ->          Type the permissions in octal
+            \tEnter ->          Apply the permissions
+            \tEsc | q ->        Close without changes
+
+            Columns
+            -------
+            \tj | k ->          Up / Down selection
+            \tSpace | x ->      Show / Hide the selected column
+            \tJ | K ->          Move the column down / up
+            \th | l ->          Narrower / Wider
+            \tr ->              Default width
+            \tEnter ->          Apply and save in settings.toml
+            \tEsc | q ->        Close without changes
 
             Help Overview
             -------------
@@ -0,0 +1,128 @@
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::Text,
+    widgets::{Block, Cell, Clear, HighlightSpacing, Row, Table, TableState},
+};
+
+use crate::{color_theme::ColorTheme, dependencies::HandlesInput};
+
+/// Number of locations kept in the back / forward stacks
+const MAX_HISTORY: usize = 100;
+/// Number of locations listed in the Recent Locations popup
+const MAX_RECENT: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub path: PathBuf,
+    /// Name of the entry that was selected in the directory
+    pub selected: Option<String>,
+}
+
+pub struct History {
+    back: Vec<HistoryEntry>,
+    forward: Vec<HistoryEntry>,
+    /// Recently visited directories, the most recent first
+    pub recent: Vec<PathBuf>,
+    pub state: TableState,
+    pub color_theme: ColorTheme,
+}
+
+impl History {
+    pub fn new(start: &Path, read_color_theme: ColorTheme) -> History {
+        History {
+            back: vec![],
+            forward: vec![],
+            recent: vec![start.to_path_buf()],
+            state: TableState::default().with_selected(0),
+            color_theme: read_color_theme,
+        }
+    }
+
+    /// Records the location that is being left for a new one
+    pub fn push(&mut self, left: HistoryEntry) {
+        if self.back.last() != Some(&left) {
+            self.back.push(left);
+        }
+        if self.back.len() > MAX_HISTORY {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+
+    /// Records a visit to `path` in the recent locations
+    pub fn visit(&mut self, path: &Path) {
+        self.recent.retain(|recent_path| recent_path != path);
+        self.recent.insert(0, path.to_path_buf());
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    /// Steps back from `current`. Returns the location to go to.
+    pub fn back(&mut self, current: HistoryEntry) -> Option<HistoryEntry> {
+        let previous = self.back.pop()?;
+        self.forward.push(current);
+        Some(previous)
+    }
+
+    /// Steps forward from `current`. Returns the location to go to.
+    pub fn forward(&mut self, current: HistoryEntry) -> Option<HistoryEntry> {
+        let next = self.forward.pop()?;
+        self.back.push(current);
+        Some(next)
+    }
+
+    pub fn selected_recent(&self) -> Option<PathBuf> {
+        self.recent.get(self.state.selected()?).cloned()
+    }
+
+    fn next_row(&mut self) {
+        if !self.recent.is_empty() {
+            let i = self.state.selected().map_or(0, |i| (i + 1) % self.recent.len());
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous_row(&mut self) {
+        if !self.recent.is_empty() {
+            let i = self.state.selected().map_or(0, |i| if i == 0 { self.recent.len() - 1 } else { i - 1 });
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let rows = self.recent.iter().map(|path| {
+            Row::new([Cell::from(path.to_string_lossy().to_string())]).style(Style::new().fg(self.color_theme.primary))
+        });
+        let t = Table::new(rows, [Constraint::Min(10)])
+            .block(
+                Block::bordered()
+                    .border_type(ratatui::widgets::BorderType::Rounded)
+                    .title(" Recent Locations ")
+                    .title_style(Style::new().fg(self.color_theme.primary))
+                    .border_style(Style::new().fg(self.color_theme.border)),
+            )
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED).fg(self.color_theme.selector))
+            .highlight_symbol(Text::from(vec![" ▶ ".into()]))
+            .bg(Color::Black)
+            .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(t, area, &mut self.state);
+    }
+}
+
+impl HandlesInput for History {
+    fn handle_input(&mut self, event: Event) -> Result<(), String> {
+        if let Event::Key(key_event) = event && key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => self.next_row(),
+                KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use crate::explorer::{EntryType, FileEntry};
+
+// Nerd Font code points
+const FOLDER: char = '\u{f07b}';
+const LINK: char = '\u{f0c1}';
+const FILE: char = '\u{f016}';
+const RUST: char = '\u{e68b}';
+const GIT: char = '\u{e702}';
+const MAKE: char = '\u{e779}';
+const DOCKER: char = '\u{f0868}';
+const LICENSE: char = '\u{e60a}';
+const CONFIG: char = '\u{e615}';
+const SHELL: char = '\u{e795}';
+const ARCHIVE: char = '\u{f410}';
+const IMAGE: char = '\u{e60d}';
+const AUDIO: char = '\u{f001}';
+const VIDEO: char = '\u{e69f}';
+const FONT: char = '\u{f031}';
+const DATABASE: char = '\u{e706}';
+
+// Files known by their whole name
+fn name_icon(name: &str) -> Option<char> {
+    let icon = match name {
+        "Cargo.toml" | "Cargo.lock" => RUST,
+        ".gitignore" | ".gitattributes" | ".gitmodules" | ".gitconfig" => GIT,
+        "Makefile" | "makefile" | "GNUmakefile" => MAKE,
+        "Dockerfile" | "Containerfile" | ".dockerignore" => DOCKER,
+        "LICENSE" | "LICENSE.md" | "LICENSE.txt" | "COPYING" => LICENSE,
+        "CMakeLists.txt" | ".editorconfig" => CONFIG,
+        ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" => SHELL,
+        _ => return None,
+    };
+    Some(icon)
+}
+
+fn extension_icon(ext: &str) -> Option<char> {
+    let icon = match ext {
+        "rs" => RUST,
+        "toml" => '\u{e6b2}',
+        "lock" => '\u{e672}',
+        "md" => '\u{f48a}',
+        "txt" => '\u{f0219}',
+        "log" => '\u{f0331}',
+        "py" => '\u{e606}',
+        "js" => '\u{e60c}',
+        "ts" => '\u{e628}',
+        "json" => '\u{e60b}',
+        "c" => '\u{e61e}',
+        "h" | "hpp" => '\u{f0fd}',
+        "cpp" => '\u{e61d}',
+        "go" => '\u{e627}',
+        "java" => '\u{e738}',
+        "lua" => '\u{e620}',
+        "rb" => '\u{e791}',
+        "php" => '\u{e608}',
+        "vim" => '\u{e62b}',
+        "html" => '\u{e736}',
+        "css" => '\u{e749}',
+        "xml" => '\u{f05c0}',
+        "csv" => '\u{e64a}',
+        "pdf" => '\u{eaeb}',
+        "yaml" | "yml" | "ini" | "conf" | "cfg" => CONFIG,
+        "sh" | "bash" | "zsh" | "fish" => SHELL,
+        "sql" | "db" | "sqlite" => DATABASE,
+        "zip" | "tar" | "gz" | "tgz" | "xz" | "bz2" | "zst" | "7z" | "rar" => ARCHIVE,
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "ico" | "svg" => IMAGE,
+        "mp3" | "flac" | "wav" | "ogg" | "m4a" => AUDIO,
+        "mp4" | "mkv" | "webm" | "avi" | "mov" => VIDEO,
+        "ttf" | "otf" | "woff" | "woff2" => FONT,
+        _ => return None,
+    };
+    Some(icon)
+}
+
+/// Nerd Font icon shown in the Type column with `nerd_font_icons`. The special files keep their glyphs.
+pub fn nerd_icon(entry: &FileEntry) -> String {
+    let icon = match entry.e_type {
+        EntryType::Dir => FOLDER,
+        EntryType::Link => LINK,
+        EntryType::File => name_icon(&entry.name)
+            .or_else(|| {
+                let ext = Path::new(&entry.name).extension()?.to_string_lossy().to_lowercase();
+                extension_icon(&ext)
+            })
+            .unwrap_or(FILE),
+        _ => return String::from(entry.glyph()),
+    };
+    format!(" {icon} ")
+}
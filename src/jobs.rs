@@ -0,0 +1,774 @@
+use std::{
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::{self, Sender}},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, Cell, Clear, HighlightSpacing, Paragraph, Row, Table, TableState, Wrap},
+};
+
+use crate::{AppEvent, color_theme::ColorTheme, dependencies::{HandlesInput, remove_path}, explorer::EntryType, file_deps::entry_type, file_size_deps::convert, owners::{OwnerChange, chown_one}, permissions::{ModeChange, chmod_one}, walker::walk_tree};
+
+/// Size of the chunks files are copied in. Progress is reported and cancellation checked between chunks.
+const CHUNK_SIZE: usize = 1024 * 1024;
+/// Finished jobs kept in the Jobs panel
+const MAX_FINISHED_JOBS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    Copy,
+    Move,
+    Delete,
+    Chmod,
+    Chown,
+}
+
+impl JobKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobKind::Copy => "Copy",
+            JobKind::Move => "Move",
+            JobKind::Delete => "Delete",
+            JobKind::Chmod => "Chmod",
+            JobKind::Chown => "Chown",
+        }
+    }
+
+    /// Sets attributes on its items rather than copying or deleting them
+    pub fn is_attribute_change(&self) -> bool {
+        matches!(self, JobKind::Chmod | JobKind::Chown)
+    }
+}
+
+/// What a chmod or chown job sets on its items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attributes {
+    /// Permission bits
+    Mode(u32),
+    /// uid and gid, `None` keeping the one the item has
+    Owner(Option<u32>, Option<u32>),
+}
+
+/// An item of a job
+#[derive(Debug, Clone)]
+pub struct JobItem {
+    pub src: PathBuf,
+    /// Not used by a delete, a chmod or a chown
+    pub dest: PathBuf,
+    /// The destination was chosen to be overwritten when the conflicts were resolved.
+    /// An existing destination without it is left alone and the item skipped.
+    pub overwrite: bool,
+    /// Set by a chmod or chown job
+    pub attributes: Option<Attributes>,
+    /// The attributes are set on everything under the directory too
+    pub recursive: bool,
+}
+
+impl JobItem {
+    pub fn new(src: PathBuf, dest: PathBuf) -> JobItem {
+        JobItem { src, dest, overwrite: false, attributes: None, recursive: false }
+    }
+
+    /// An item of a chmod or chown job
+    pub fn with_attributes(path: PathBuf, attributes: Attributes, recursive: bool) -> JobItem {
+        JobItem { dest: path.clone(), src: path, overwrite: false, attributes: Some(attributes), recursive }
+    }
+}
+
+/// What started a job -> Decides where the job lands in the undo history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobOrigin {
+    User,
+    /// With the generation of the undo history when the job was started
+    Undo(u64),
+    Redo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    /// How many items failed. The messages are kept in `Job::failures`.
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JobProgress {
+    pub total_bytes: u64,
+    pub done_bytes: u64,
+    pub total_files: u64,
+    pub done_files: u64,
+    /// Path of the item being worked on
+    pub current: String,
+}
+
+/// What a finished job reports back
+#[derive(Debug, Default)]
+pub struct JobOutcome {
+    /// A message for every item that failed
+    pub failures: Vec<String>,
+    /// Destinations removed to be overwritten
+    pub overwritten: Vec<PathBuf>,
+    /// Permissions changed by a chmod job
+    pub mode_changes: Vec<ModeChange>,
+    /// Owners changed by a chown job
+    pub owner_changes: Vec<OwnerChange>,
+}
+
+pub struct Job {
+    pub id: u64,
+    pub kind: JobKind,
+    pub items: Vec<JobItem>,
+    pub origin: JobOrigin,
+    pub status: JobStatus,
+    pub progress: JobProgress,
+    pub started: Option<Instant>,
+    /// A message for every item that failed, listed under the jobs in the Jobs panel
+    pub failures: Vec<String>,
+    /// Destinations the job removed to overwrite them -> It can't be undone
+    pub overwritten: Vec<PathBuf>,
+    /// Permissions the job changed
+    pub mode_changes: Vec<ModeChange>,
+    /// Owners the job changed
+    pub owner_changes: Vec<OwnerChange>,
+    cancel: Arc<AtomicBool>,
+}
+
+struct JobRequest {
+    id: u64,
+    kind: JobKind,
+    items: Vec<JobItem>,
+    cancel: Arc<AtomicBool>,
+}
+
+pub struct Jobs {
+    pub jobs: Vec<Job>,
+    next_id: u64,
+    job_tx: Sender<JobRequest>,
+    pub state: TableState,
+    /// Lines scrolled in the failures of the selected job
+    failures_scroll: u16,
+    pub color_theme: ColorTheme,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlanKind {
+    Dir,
+    File,
+    Link,
+    Fifo,
+    /// A socket or a device, with its label
+    Other(&'static str),
+}
+
+// A single entry to copy or delete, found while scanning the items of a job
+struct PlanEntry {
+    src: PathBuf,
+    dest: PathBuf,
+    kind: PlanKind,
+    size: u64,
+}
+
+// Walks `src` without following symlinks. Directories come before their contents.
+fn plan_tree(src: &Path, dest: &Path, plan: &mut Vec<PlanEntry>, cancel: &AtomicBool) -> Result<(), String> {
+    if cancel.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let meta = src.symlink_metadata().map_err(|err| format!("{}: {err}", src.to_string_lossy()))?;
+    let kind = match entry_type(meta.file_type()) {
+        EntryType::Dir => PlanKind::Dir,
+        EntryType::Link => PlanKind::Link,
+        EntryType::File => PlanKind::File,
+        EntryType::Fifo => PlanKind::Fifo,
+        other => PlanKind::Other(other.label()),
+    };
+    plan.push(PlanEntry {
+        src: src.to_path_buf(),
+        dest: dest.to_path_buf(),
+        kind,
+        size: if kind == PlanKind::File { meta.len() } else { 0 },
+    });
+    if kind == PlanKind::Dir {
+        let read_dir = fs::read_dir(src).map_err(|err| format!("{}: {err}", src.to_string_lossy()))?;
+        for entry in read_dir.flatten() {
+            plan_tree(&entry.path(), &dest.join(entry.file_name()), plan, cancel)?;
+        }
+    }
+    Ok(())
+}
+
+// Runs a job on the worker thread and keeps the UI posted about its progress
+struct Runner<'a> {
+    id: u64,
+    cancel: &'a AtomicBool,
+    tx: &'a Sender<AppEvent>,
+    progress: JobProgress,
+    last_sent: Instant,
+    failed: Vec<String>,
+    overwritten: Vec<PathBuf>,
+    mode_changes: Vec<ModeChange>,
+    owner_changes: Vec<OwnerChange>,
+}
+
+impl Runner<'_> {
+    fn report(&mut self, force: bool) {
+        if force || self.last_sent.elapsed() >= Duration::from_millis(100) {
+            let _ = self.tx.send(AppEvent::JobProgress(self.id, self.progress.clone()));
+            self.last_sent = Instant::now();
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    fn plan(&mut self, items: &[JobItem]) -> Vec<PlanEntry> {
+        let mut plan = vec![];
+        for JobItem { src, dest, .. } in items {
+            self.progress.current = src.to_string_lossy().to_string();
+            self.report(false);
+            if let Err(err) = plan_tree(src, dest, &mut plan, self.cancel) {
+                self.failed.push(err);
+            }
+        }
+        self.progress.total_files += plan.iter().filter(|entry| entry.kind != PlanKind::Dir).count() as u64;
+        self.progress.total_bytes += plan.iter().map(|entry| entry.size).sum::<u64>();
+        plan
+    }
+
+    fn copy_file(&mut self, src: &Path, dest: &Path) -> Result<(), String> {
+        let mut reader = File::open(src).map_err(|err| err.to_string())?;
+        let mut writer = File::create(dest).map_err(|err| err.to_string())?;
+        let mut buf = vec![0_u8; CHUNK_SIZE];
+        loop {
+            if self.cancelled() {
+                drop(writer);
+                let _ = fs::remove_file(dest);
+                return Err(String::from("Cancelled"));
+            }
+            let read = reader.read(&mut buf).map_err(|err| err.to_string())?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buf[..read]).map_err(|err| err.to_string())?;
+            self.progress.done_bytes += read as u64;
+            self.report(false);
+        }
+        if let Ok(meta) = fs::metadata(src) {
+            let _ = fs::set_permissions(dest, meta.permissions());
+        }
+        Ok(())
+    }
+
+    fn copy_entry(&mut self, entry: &PlanEntry) -> Result<(), String> {
+        match entry.kind {
+            PlanKind::Dir => fs::create_dir_all(&entry.dest).map_err(|err| err.to_string()),
+            PlanKind::File => self.copy_file(&entry.src, &entry.dest),
+            PlanKind::Link => copy_link(&entry.src, &entry.dest),
+            // A FIFO is made again rather than read, reading it would wait for a writer
+            PlanKind::Fifo => copy_fifo(&entry.src, &entry.dest),
+            PlanKind::Other(label) => Err(format!("A {label} can't be copied, skipped")),
+        }
+    }
+
+    // Copies the planned entries. Returns false if any of them failed.
+    fn copy_plan(&mut self, plan: &[PlanEntry]) -> bool {
+        let mut all_copied = true;
+        for entry in plan {
+            if self.cancelled() {
+                return false;
+            }
+            self.progress.current = entry.src.to_string_lossy().to_string();
+            self.report(false);
+            if let Err(err) = self.copy_entry(entry) {
+                self.failed.push(format!("{}: {err}", entry.src.to_string_lossy()));
+                all_copied = false;
+            }
+            if entry.kind != PlanKind::Dir {
+                self.progress.done_files += 1;
+            }
+        }
+        all_copied
+    }
+
+    // Deletes the planned entries, the contents of a directory before the directory itself
+    fn delete_plan(&mut self, plan: &[PlanEntry], count_files: bool) {
+        for entry in plan.iter().rev() {
+            if self.cancelled() {
+                return;
+            }
+            self.progress.current = entry.src.to_string_lossy().to_string();
+            self.report(false);
+            let result = if entry.kind == PlanKind::Dir { fs::remove_dir(&entry.src) } else { fs::remove_file(&entry.src) };
+            if let Err(err) = result {
+                self.failed.push(format!("{}: {err}", entry.src.to_string_lossy()));
+            }
+            if count_files && entry.kind != PlanKind::Dir {
+                self.progress.done_files += 1;
+            }
+        }
+    }
+
+    fn run(&mut self, kind: JobKind, items: &[JobItem]) {
+        match kind {
+            JobKind::Copy => {
+                let items = self.refuse_into_itself(items);
+                let items = self.clear_destinations(items);
+                let plan = self.plan(&items);
+                self.copy_plan(&plan);
+            },
+            JobKind::Move => {
+                // A rename is tried first. Only what can't be renamed (across file systems) is copied and then deleted.
+                let mut to_copy = vec![];
+                let items = self.refuse_into_itself(items);
+                for item in self.clear_destinations(items) {
+                    self.progress.current = item.src.to_string_lossy().to_string();
+                    self.report(false);
+                    if fs::rename(&item.src, &item.dest).is_err() {
+                        to_copy.push(item);
+                    }
+                }
+                let plan = self.plan(&to_copy);
+                if self.copy_plan(&plan) {
+                    self.delete_plan(&plan, false);
+                }
+            },
+            JobKind::Delete => {
+                let plan = self.plan(items);
+                self.delete_plan(&plan, true);
+            },
+            JobKind::Chmod | JobKind::Chown => self.set_attributes(items),
+        }
+    }
+
+    // Sets the attributes of the items, and of everything under the recursive ones. The trees are walked here
+    // rather than when the job is queued, they can be large.
+    fn set_attributes(&mut self, items: &[JobItem]) {
+        let cancel = self.cancel;
+        let mut targets: Vec<(PathBuf, Attributes)> = vec![];
+        for item in items {
+            let Some(attributes) = item.attributes else {
+                continue;
+            };
+            self.progress.current = item.src.to_string_lossy().to_string();
+            self.report(false);
+            targets.push((item.src.clone(), attributes));
+            if item.recursive && item.src.symlink_metadata().is_ok_and(|meta| meta.is_dir()) {
+                walk_tree(&item.src, true, cancel, |entry_path, _is_dir| targets.push((entry_path.to_path_buf(), attributes)));
+            }
+        }
+        self.progress.total_files = targets.len() as u64;
+        for (path, attributes) in targets {
+            if self.cancelled() {
+                return;
+            }
+            self.progress.current = path.to_string_lossy().to_string();
+            self.report(false);
+            let result = match attributes {
+                Attributes::Mode(mode) => chmod_one(&path, mode).map(|change| self.mode_changes.extend(change)),
+                Attributes::Owner(uid, gid) => chown_one(&path, uid, gid).map(|change| self.owner_changes.extend(change)),
+            };
+            if let Err(err) = result {
+                self.failed.push(err);
+            }
+            self.progress.done_files += 1;
+        }
+    }
+
+    // Conflicts are resolved when a job is queued, but the destinations can be taken by then (by an earlier job
+    // or by another program). Only the destinations chosen to be overwritten are removed, the items running
+    // into another one are skipped. Returns the items to go on with.
+    fn clear_destinations(&mut self, items: Vec<JobItem>) -> Vec<JobItem> {
+        items
+            .into_iter()
+            .filter(|JobItem { src, dest, overwrite, .. }| {
+                if dest.symlink_metadata().is_err() {
+                    return true;
+                }
+                if !overwrite || src.starts_with(dest) {
+                    self.failed.push(format!("{}: already exists, skipped", dest.to_string_lossy()));
+                    return false;
+                }
+                match remove_path(dest) {
+                    Ok(()) => {
+                        self.overwritten.push(dest.clone());
+                        true
+                    },
+                    Err(err) => {
+                        self.failed.push(format!("{}: {err}", dest.to_string_lossy()));
+                        false
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn refuse_into_itself(&mut self, items: &[JobItem]) -> Vec<JobItem> {
+        items
+            .iter()
+            .filter(|JobItem { src, dest, .. }| {
+                if dest.starts_with(src) {
+                    self.failed.push(format!("{}: can't be put inside itself", src.to_string_lossy()));
+                    false
+                } else {
+                    true
+                }
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(unix)]
+fn copy_link(src: &Path, dest: &Path) -> Result<(), String> {
+    let target = fs::read_link(src).map_err(|err| err.to_string())?;
+    std::os::unix::fs::symlink(target, dest).map_err(|err| err.to_string())
+}
+
+#[cfg(not(unix))]
+fn copy_link(src: &Path, dest: &Path) -> Result<(), String> {
+    fs::copy(src, dest).map(|_| ()).map_err(|err| err.to_string())
+}
+
+#[cfg(unix)]
+fn copy_fifo(src: &Path, dest: &Path) -> Result<(), String> {
+    use std::{ffi::CString, os::unix::{ffi::OsStrExt, fs::PermissionsExt}};
+    let mode = src.symlink_metadata().map(|meta| meta.permissions().mode() & 0o7777).unwrap_or(0o644);
+    let dest_c = CString::new(dest.as_os_str().as_bytes()).map_err(|err| err.to_string())?;
+    // SAFETY: `dest_c` is a valid NUL-terminated path that outlives the call
+    if unsafe { libc::mkfifo(dest_c.as_ptr(), mode as libc::mode_t) } != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_fifo(_src: &Path, _dest: &Path) -> Result<(), String> {
+    Err(String::from("Not a regular file, skipped"))
+}
+
+// Runs on the jobs thread. Jobs are run one after the other in the order they were queued.
+fn job_worker(job_rx: mpsc::Receiver<JobRequest>, event_tx: Sender<AppEvent>) {
+    while let Ok(request) = job_rx.recv() {
+        if request.cancel.load(Ordering::Relaxed) {
+            let _ = event_tx.send(AppEvent::JobDone(request.id, JobOutcome::default()));
+            continue;
+        }
+        let _ = event_tx.send(AppEvent::JobProgress(request.id, JobProgress::default()));
+        let mut runner = Runner {
+            id: request.id,
+            cancel: &request.cancel,
+            tx: &event_tx,
+            progress: JobProgress::default(),
+            last_sent: Instant::now(),
+            failed: vec![],
+            overwritten: vec![],
+            mode_changes: vec![],
+            owner_changes: vec![],
+        };
+        runner.run(request.kind, &request.items);
+        runner.progress.current.clear();
+        runner.report(true);
+        let outcome = JobOutcome {
+            failures: runner.failed,
+            overwritten: runner.overwritten,
+            mode_changes: runner.mode_changes,
+            owner_changes: runner.owner_changes,
+        };
+        if event_tx.send(AppEvent::JobDone(request.id, outcome)).is_err() {
+            break;
+        }
+    }
+}
+
+impl Job {
+    /// Estimated time left, from the rate the job went at so far
+    pub fn eta(&self) -> Option<Duration> {
+        let elapsed = self.started?.elapsed().as_secs_f64();
+        let (done, total) = if self.progress.total_bytes > 0 {
+            (self.progress.done_bytes, self.progress.total_bytes)
+        } else {
+            (self.progress.done_files, self.progress.total_files)
+        };
+        if done == 0 || total < done {
+            return None;
+        }
+        Some(Duration::from_secs_f64(elapsed * (total - done) as f64 / done as f64))
+    }
+
+    pub fn percent(&self) -> u64 {
+        (self.progress.done_bytes * 100)
+            .checked_div(self.progress.total_bytes)
+            .or_else(|| (self.progress.done_files * 100).checked_div(self.progress.total_files))
+            .unwrap_or(0)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !matches!(self.status, JobStatus::Queued | JobStatus::Running)
+    }
+
+    fn description(&self) -> String {
+        let target = match self.kind {
+            JobKind::Delete | JobKind::Chmod | JobKind::Chown => String::new(),
+            _ => self.items
+                .first()
+                .and_then(|item| item.dest.parent())
+                .map(|dest_dir| format!(" to {}", dest_dir.to_string_lossy()))
+                .unwrap_or_default(),
+        };
+        format!("{} {} item(s){}", self.kind.label(), self.items.len(), target)
+    }
+
+    fn status_text(&self) -> String {
+        match &self.status {
+            JobStatus::Queued => String::from("Queued"),
+            JobStatus::Running if self.kind.is_attribute_change() => {
+                format!("{}% {}/{} items", self.percent(), self.progress.done_files, self.progress.total_files)
+            },
+            JobStatus::Running => {
+                let eta = self.eta().map(|eta| format!(", ETA {}s", eta.as_secs())).unwrap_or_default();
+                format!(
+                    "{}% {} / {}, {}/{} files{}",
+                    self.percent(),
+                    convert(self.progress.done_bytes as f64),
+                    convert(self.progress.total_bytes as f64),
+                    self.progress.done_files,
+                    self.progress.total_files,
+                    eta
+                )
+            },
+            JobStatus::Done => String::from("Done"),
+            JobStatus::Failed(err) => format!("Failed: {err}"),
+            JobStatus::Cancelled => String::from("Cancelled"),
+        }
+    }
+}
+
+impl Jobs {
+    pub fn new(event_tx: Sender<AppEvent>, read_color_theme: ColorTheme) -> Jobs {
+        let (job_tx, job_rx) = mpsc::channel::<JobRequest>();
+        thread::spawn(move || job_worker(job_rx, event_tx));
+        Jobs {
+            jobs: vec![],
+            next_id: 0,
+            job_tx,
+            state: TableState::default(),
+            failures_scroll: 0,
+            color_theme: read_color_theme,
+        }
+    }
+
+    /// Queues a job. It starts once the jobs queued before it are finished.
+    pub fn enqueue(&mut self, kind: JobKind, items: Vec<JobItem>, origin: JobOrigin) {
+        if items.is_empty() {
+            return;
+        }
+        self.next_id += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let request = JobRequest { id: self.next_id, kind, items: items.clone(), cancel: Arc::clone(&cancel) };
+        self.jobs.push(Job {
+            id: self.next_id,
+            kind,
+            items,
+            origin,
+            status: JobStatus::Queued,
+            progress: JobProgress::default(),
+            started: None,
+            failures: vec![],
+            overwritten: vec![],
+            mode_changes: vec![],
+            owner_changes: vec![],
+            cancel,
+        });
+        let _ = self.job_tx.send(request);
+        // Dropping the oldest finished jobs
+        while self.jobs.iter().filter(|job| job.is_finished()).count() > MAX_FINISHED_JOBS {
+            if let Some(idx) = self.jobs.iter().position(|job| job.is_finished()) {
+                self.jobs.remove(idx);
+            }
+        }
+        if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn set_progress(&mut self, id: u64, progress: JobProgress) {
+        if let Some(job) = self.jobs.iter_mut().find(|job| job.id == id) {
+            if job.status == JobStatus::Queued {
+                job.status = JobStatus::Running;
+                job.started = Some(Instant::now());
+            }
+            job.progress = progress;
+        }
+    }
+
+    /// Records the end of a job and returns it
+    pub fn finish(&mut self, id: u64, outcome: JobOutcome) -> Option<&Job> {
+        let job = self.jobs.iter_mut().find(|job| job.id == id)?;
+        job.status = if job.cancel.load(Ordering::Relaxed) {
+            JobStatus::Cancelled
+        } else if outcome.failures.is_empty() {
+            JobStatus::Done
+        } else {
+            JobStatus::Failed(format!("{} item(s)", outcome.failures.len()))
+        };
+        job.failures = outcome.failures;
+        job.overwritten = outcome.overwritten;
+        job.mode_changes = outcome.mode_changes;
+        job.owner_changes = outcome.owner_changes;
+        Some(job)
+    }
+
+    /// Cancels the selected job, whether it is running or still queued
+    pub fn cancel_selected(&mut self) {
+        if let Some(job) = self.state.selected().and_then(|idx| self.jobs.get(idx)) && !job.is_finished() {
+            job.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(|job| !job.is_finished());
+        self.failures_scroll = 0;
+        self.state.select(if self.jobs.is_empty() { None } else { Some(0) });
+    }
+
+    /// One line summary of the jobs that aren't finished, for the status line
+    pub fn summary(&self) -> Option<String> {
+        let active: Vec<&Job> = self.jobs.iter().filter(|job| !job.is_finished()).collect();
+        let running = active.iter().find(|job| job.status == JobStatus::Running)?;
+        let eta = running.eta().map(|eta| format!(" ETA {}s", eta.as_secs())).unwrap_or_default();
+        Some(format!(
+            "{} {}%{} ({} job(s) left) ",
+            running.kind.label(),
+            running.percent(),
+            eta,
+            active.len()
+        ))
+    }
+
+    fn next_row(&mut self) {
+        self.failures_scroll = 0;
+        if !self.jobs.is_empty() {
+            let i = self.state.selected().map_or(0, |i| (i + 1) % self.jobs.len());
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous_row(&mut self) {
+        self.failures_scroll = 0;
+        if !self.jobs.is_empty() {
+            let i = self.state.selected().map_or(0, |i| if i == 0 { self.jobs.len() - 1 } else { i - 1 });
+            self.state.select(Some(i));
+        }
+    }
+
+    fn scroll_failures(&mut self, down: bool) {
+        let failures = self.state.selected().and_then(|idx| self.jobs.get(idx)).map_or(0, |job| job.failures.len());
+        self.failures_scroll = if down {
+            (self.failures_scroll + 1).min(failures.saturating_sub(1) as u16)
+        } else {
+            self.failures_scroll.saturating_sub(1)
+        };
+    }
+
+    // The failures of the selected job, below the jobs
+    fn render_failures(&self, frame: &mut Frame, area: Rect, job: &Job) {
+        let lines: Vec<Line> = job.failures.iter().map(|failure| Line::from(format!(" {failure}"))).collect();
+        let instructions = Line::from(vec![" <J/K>".blue().bold(), " Scroll ".into()]).right_aligned();
+        let block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title(format!(" Failures of #{} ({}) ", job.id, job.failures.len()))
+            .title_bottom(instructions)
+            .title_style(Style::new().fg(Color::Red))
+            .border_style(Style::new().fg(self.color_theme.border));
+        let failures = Paragraph::new(lines)
+            .style(Style::new().fg(self.color_theme.primary))
+            .bg(Color::Black)
+            .wrap(Wrap { trim: false })
+            .scroll((self.failures_scroll, 0))
+            .block(block);
+        frame.render_widget(failures, area);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let area = match self.state.selected().and_then(|idx| self.jobs.get(idx)).filter(|job| !job.failures.is_empty()) {
+            Some(job) => {
+                let [jobs_area, failures_area] = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+                self.render_failures(frame, failures_area, job);
+                jobs_area
+            },
+            None => area,
+        };
+        let instructions = Line::from(vec![
+            " <c>".blue().bold(),
+            " Cancel ".into(),
+            "<C>".blue().bold(),
+            " Clear finished ".into(),
+            "<Esc>".blue().bold(),
+            " Close ".into(),
+        ]).right_aligned();
+        let block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title(" Jobs ")
+            .title_bottom(instructions)
+            .title_style(Style::new().fg(self.color_theme.primary))
+            .border_style(Style::new().fg(self.color_theme.border));
+        if self.jobs.is_empty() {
+            frame.render_widget(Paragraph::new(" No jobs").bg(Color::Black).block(block), area);
+            return;
+        }
+        let current_style = Style::default().fg(Color::DarkGray);
+        let rows = self.jobs.iter().map(|job| {
+            let status_style = match job.status {
+                JobStatus::Failed(_) => Style::default().fg(Color::Red),
+                JobStatus::Done => Style::default().fg(Color::Green),
+                _ => Style::default().fg(self.color_theme.primary),
+            };
+            Row::new([
+                Cell::from(Text::from(vec![
+                    Line::from(format!("#{} {}", job.id, job.description())),
+                    Line::styled(job.progress.current.clone(), current_style),
+                ])),
+                Cell::from(job.status_text()).style(status_style),
+            ])
+            .height(2)
+            .style(Style::new().fg(self.color_theme.primary))
+        });
+        let t = Table::new(rows, [Constraint::Percentage(60), Constraint::Percentage(40)])
+            .block(block)
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED).fg(self.color_theme.selector))
+            .highlight_symbol(Text::from(vec![" ▶ ".into()]))
+            .bg(Color::Black)
+            .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(t, area, &mut self.state);
+    }
+}
+
+impl HandlesInput for Jobs {
+    fn handle_input(&mut self, event: Event) -> Result<(), String> {
+        if let Event::Key(key_event) = event && key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => self.next_row(),
+                KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
+                KeyCode::Char('c') => self.cancel_selected(),
+                KeyCode::Char('C') => self.clear_finished(),
+                KeyCode::Char('J') => self.scroll_failures(true),
+                KeyCode::Char('K') => self.scroll_failures(false),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
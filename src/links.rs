@@ -0,0 +1,159 @@
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{App, batch_rename::split_args, undo::Operation};
+
+// Removes the `.` and `..` of a path without touching the disk
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                normalized.pop();
+            },
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// `to` written relative to the directory `from_dir`. Both are absolute.
+pub fn relative_path(from_dir: &Path, to: &Path) -> PathBuf {
+    let (from, to) = (normalize(from_dir), normalize(to));
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push("..");
+    }
+    for component in &to[common..] {
+        relative.push(component);
+    }
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
+/// Where the symlink at `link` leads to. The whole chain of links is followed when it can be.
+pub fn resolve_link(link: &Path, target: &Path) -> PathBuf {
+    link.canonicalize().unwrap_or_else(|_| match link.parent() {
+        Some(dir) => normalize(&dir.join(target)),
+        None => target.to_path_buf(),
+    })
+}
+
+/// Creates the link at `link`. A symbolic link stores `target` as it is, a hard link needs it to exist.
+pub fn create_link(target: &Path, link: &Path, symbolic: bool) -> io::Result<()> {
+    if !symbolic {
+        return fs::hard_link(target, link);
+    }
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(target, link)
+    }
+    #[cfg(windows)]
+    {
+        // The target is relative to the directory of the link
+        let resolved = link.parent().map(|dir| dir.join(target)).unwrap_or(target.to_path_buf());
+        if resolved.is_dir() {
+            std::os::windows::fs::symlink_dir(target, link)
+        } else {
+            std::os::windows::fs::symlink_file(target, link)
+        }
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "no symlinks on this system"))
+    }
+}
+
+/// Moves the Explorer to the directory holding the target of the selected symlink and selects the target
+pub fn jump_to_link_target(app: &mut App) -> Result<(), String> {
+    let Some(entry) = app.explorer.state.selected().and_then(|idx| app.explorer.files.get(idx)) else {
+        return Ok(());
+    };
+    let Some(link) = &entry.link else {
+        return Err(String::from("The selected item isn't a symlink"));
+    };
+    let target = resolve_link(&app.explorer.root_path.join(&entry.name), &link.path);
+    if link.broken {
+        return Err(format!("The link is broken, {} doesn't exist", target.to_string_lossy()));
+    }
+    let (Some(dir), Some(name)) = (target.parent(), target.file_name()) else {
+        app.navigate_to(target);
+        return Ok(());
+    };
+    app.navigate_to(dir.to_path_buf());
+    app.explorer.select_name(&name.to_string_lossy());
+    Ok(())
+}
+
+/// Applies the `ln [-s] [-r] <TARGET> [LINK_NAME]` command. Paths are relative to the directory of the Explorer.
+/// `-s` makes a symbolic link with an absolute target, `-r` a relative one. Without `-s` a hard link is made.
+pub fn ln_command(app: &mut App, args: &str) -> Result<(), String> {
+    const USAGE: &str = "Usage: ln [-s] [-r] <TARGET> [LINK_NAME]";
+    let mut symbolic = false;
+    let mut relative = false;
+    let mut names: Vec<String> = vec![];
+    for arg in split_args(args) {
+        match arg.strip_prefix('-') {
+            Some(flags) if !flags.is_empty() && flags.chars().all(|flag| flag == 's' || flag == 'r') => {
+                symbolic |= flags.contains('s');
+                relative |= flags.contains('r');
+            },
+            _ => names.push(arg),
+        }
+    }
+    let (target_name, link_name) = match names.as_slice() {
+        [target] => (target, None),
+        [target, link] => (target, Some(link)),
+        _ => return Err(String::from(USAGE)),
+    };
+    if relative && !symbolic {
+        return Err(String::from("-r only makes sense with -s"));
+    }
+    let dir = app.explorer.root_path.clone();
+    let target = normalize(&dir.join(target_name));
+    let Some(target_file_name) = target.file_name() else {
+        return Err(String::from(USAGE));
+    };
+    let mut link = match link_name {
+        Some(link_name) => normalize(&dir.join(link_name)),
+        None => dir.join(target_file_name),
+    };
+    // Like `ln`, a directory given as the link name gets the link inside it
+    if link_name.is_some() && link.is_dir() {
+        link.push(target_file_name);
+    }
+    if link.symlink_metadata().is_ok() {
+        return Err(format!("{} already exists", link.to_string_lossy()));
+    }
+    let stored_target = match link.parent() {
+        Some(link_dir) if relative => relative_path(link_dir, &target),
+        _ => target.clone(),
+    };
+    if !symbolic && target.is_dir() {
+        return Err(String::from("Hard links to directories are not allowed, use ln -s"));
+    }
+    create_link(&stored_target, &link, symbolic).map_err(|err| format!("Unable to create the link: {err}"))?;
+    app.log_panel.set_log(format!(
+        "Created the {} link {} -> {}",
+        if symbolic { "symbolic" } else { "hard" },
+        link.to_string_lossy(),
+        stored_target.to_string_lossy()
+    ));
+    app.undo.record(Operation::Link { path: link.clone(), target: stored_target, symbolic });
+    app.explorer.reload();
+    if let Some(other) = app.other_pane.as_mut() {
+        other.explorer.reload();
+    }
+    if link.parent() == Some(dir.as_path()) && let Some(name) = link.file_name() {
+        app.explorer.select_name(&name.to_string_lossy());
+    }
+    Ok(())
+}
@@ -1,9 +1,9 @@
 use clap::Parser;
 use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::{
-    DefaultTerminal, Frame, layout::{Constraint, Direction, Layout, Rect}, style::Stylize, widgets::{Block, BorderType, Paragraph}
+    DefaultTerminal, Frame, layout::{Constraint, Direction, Layout, Position, Rect}, style::Stylize, text::Line, widgets::{Block, BorderType, Paragraph}
 };
-use std::{env::current_dir, path::{Path, PathBuf}, sync::mpsc, thread::{self}};
+use std::{env::current_dir, path::{Path, PathBuf}, sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc}, thread::{self}, time::{Duration, Instant, SystemTime}};
 use strum::{EnumIter, IntoEnumIterator};
 
 mod command;
@@ -18,9 +18,34 @@ mod open_files;
 mod log_panel;
 mod help_overview;
 mod color_theme;
+mod sort;
+mod settings;
+mod filter;
+mod walker;
+mod finder;
+mod grep;
+mod preview;
+mod history;
+mod tabs;
+mod dual_pane;
+mod jobs;
+mod conflict;
+mod undo;
+mod bulk_rename;
+mod mouse;
+mod watcher;
+mod dir_sizes;
+mod disk_usage;
+mod columns;
+mod links;
+mod owners;
+mod permissions;
+mod batch_rename;
+mod file_colors;
+mod icons;
 
 use crate::{
-    color_theme::{get_color_theme}, command::{Command, handle_command_enter}, dependencies::{HandlesInput, InputMode, focus_to, focus_toggler}, drives::Drives, explorer::{Explorer, explorer_handle_enter}, help_overview::HelpOverview, log_panel::LogPanel, path_field::PathField, quick_access::{QuickAccess, update_qa_files, write_qa_data}
+    batch_rename::render_rename_preview, bulk_rename::bulk_rename, color_theme::{get_color_theme}, columns::{ColumnPicker, apply_columns, open_column_picker}, command::{Command, handle_command_enter}, conflict::{ConflictPolicy, PendingOperation, conflict_handle_key, paste_clipboard}, dir_sizes::{DirSizes, DirSummary, resort_by_size}, disk_usage::{DiskUsage, DuScan, disk_usage_delete}, permissions::{ChmodEditor, apply_chmod, open_chmod_editor}, dependencies::{HandlesInput, InputMode, focus_to, focus_toggler}, drives::Drives, dual_pane::{open_dual_pane, switch_pane, toggle_dual_pane, transfer_to_other_pane}, explorer::{Clipboard, Explorer, explorer_handle_enter}, links::jump_to_link_target, finder::{Finder, FinderResults, finder_handle_enter}, grep::{GrepHit, GrepSearch, grep_handle_enter, grep_reveal}, help_overview::HelpOverview, history::{History, HistoryEntry}, jobs::{JobKind, JobOutcome, JobProgress, JobStatus, Jobs}, log_panel::LogPanel, mouse::{disable_mouse_capture, enable_mouse_capture, handle_mouse}, preview::Preview, path_field::PathField, quick_access::{QuickAccess, update_qa_files, write_qa_data}, settings::{read_settings, settings_path}, tabs::{Tab, close_tab, new_tab, next_tab, previous_tab, render_tab_bar}, undo::{Operation, UndoHistory, redo, undo}, watcher::DirWatcher
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter)]
@@ -62,6 +87,65 @@ pub struct App {
     log_panel: LogPanel,
     help_overview: HelpOverview,
     help_shown: bool,
+    finder: Finder,
+    finder_shown: bool,
+    grep: GrepSearch,
+    grep_shown: bool,
+    preview: Preview,
+    history: History,
+    history_shown: bool,
+    /// Slots of the open tabs. The slot of the active tab is outdated, its live state is in `explorer` and `history`.
+    tabs: Vec<Tab>,
+    active_tab: usize,
+    /// Shared by all the tabs to copy / move items between them
+    clipboard: Clipboard,
+    /// The pane that isn't active in the dual-pane mode, `None` when the mode is off
+    other_pane: Option<Tab>,
+    left_pane_active: bool,
+    jobs: Jobs,
+    jobs_shown: bool,
+    /// Paste, move or rename waiting on the conflict prompt
+    pending_operation: Option<PendingOperation>,
+    conflict_policy: ConflictPolicy,
+    undo: UndoHistory,
+    /// Set on `E`. The bulk rename needs the terminal, so it is run from the main loop.
+    bulk_rename_requested: bool,
+    /// Stops the input thread from reading the terminal while an external program uses it
+    input_paused: Arc<AtomicBool>,
+    /// Time and cell of the last click, to recognize a double click
+    last_click: Option<(Instant, Position)>,
+    /// Keeps the listings of the panes up to date with the changes on disk
+    watcher: DirWatcher,
+    dir_sizes: DirSizes,
+    disk_usage: DiskUsage,
+    disk_usage_shown: bool,
+    /// The chmod popup, while it is open
+    chmod_editor: Option<ChmodEditor>,
+    /// The column picker popup, while it is open
+    column_picker: Option<ColumnPicker>,
+    /// Where the column layout is saved
+    settings_path: PathBuf,
+    /// Handed over to the background workers so that they can send their results to the UI
+    event_tx: mpsc::Sender<AppEvent>,
+}
+
+/// Everything the main loop reacts to -> Terminal events and results from background workers
+pub enum AppEvent {
+    Input(Event),
+    FinderResults(u64, FinderResults),
+    GrepBatch(u64, Vec<GrepHit>),
+    GrepDone(u64),
+    Preview(u64, Vec<Line<'static>>),
+    JobProgress(u64, JobProgress),
+    JobDone(u64, JobOutcome),
+    /// Entries were created, removed or renamed in the directory
+    DirChanged(PathBuf),
+    /// Summary of a directory (request generation, path, modification time it was listed with, summary)
+    DirSize(u64, PathBuf, Option<SystemTime>, DirSummary),
+    DirSizesDone(u64),
+    /// Entries scanned so far by the disk usage scan
+    DiskUsageProgress(u64, u64),
+    DiskUsageDone(u64, Box<DuScan>),
 }
 
 #[derive(Debug, Parser)]
@@ -94,146 +178,364 @@ struct CLI {
         help = "Path to color_theme.toml file (if not in default config directory)"
     )]
     color_theme_path: Option<PathBuf>,
+    #[arg(
+        short = 's',
+        long = "settings",
+        help = "Path to settings.toml file (if not in default config directory)"
+    )]
+    settings_path: Option<PathBuf>,
+    #[arg(
+        long = "dual",
+        num_args = 2,
+        value_names = ["LEFT", "RIGHT"],
+        conflicts_with = "path",
+        help = "Starts in the dual-pane mode with the given directories"
+    )]
+    dual: Option<Vec<PathBuf>>,
 }
 
 impl App {
-    fn run(&mut self, terminal: &mut DefaultTerminal, rx: mpsc::Receiver<Event>) -> Result<(), String> {
+    fn run(&mut self, terminal: &mut DefaultTerminal, rx: mpsc::Receiver<AppEvent>) -> Result<(), String> {
         terminal.draw(|frame| self.draw(frame)).expect("Unable to draw to the terminal");
         while !self.exit {
-            if let Ok(rec_event) = rx.recv() {
-                match rec_event {
-                    Event::Key(key_event) => {
-                        if key_event.kind == KeyEventKind::Press {
-                            // Clearing log output before handling the operation
-                            self.log_panel.clear_log();
-                            if self.help_shown {
-                                match key_event.code {
-                                    KeyCode::Char('q') => self.help_shown = false,
-                                    KeyCode::Char('j') | KeyCode::Down => self.help_overview.scroll = if self.help_overview.scroll >= self.help_overview.max_scroll {self.help_overview.max_scroll} else {self.help_overview.scroll + 1},
-                                    KeyCode::Char('k') | KeyCode::Up => self.help_overview.scroll = self.help_overview.scroll.saturating_sub(1),
-                                    _ => (),
+            if let Ok(app_event) = rx.recv() {
+                match app_event {
+                    AppEvent::Input(rec_event) => self.handle_event(rec_event)?,
+                    AppEvent::FinderResults(generation, results) => self.finder.set_results(generation, results),
+                    AppEvent::GrepBatch(generation, hits) => self.grep.add_hits(generation, hits),
+                    AppEvent::GrepDone(generation) => self.grep.finish(generation),
+                    AppEvent::Preview(id, lines) => self.preview.set_content(id, lines),
+                    AppEvent::JobProgress(id, progress) => self.jobs.set_progress(id, progress),
+                    AppEvent::JobDone(id, outcome) => self.handle_job_done(id, outcome),
+                    AppEvent::DirChanged(dir_path) => self.handle_dir_changed(dir_path),
+                    AppEvent::DirSize(generation, dir_path, modified, summary) => self.dir_sizes.set_result(generation, dir_path, modified, summary),
+                    AppEvent::DiskUsageProgress(generation, scanned) => self.disk_usage.set_progress(generation, scanned),
+                    AppEvent::DiskUsageDone(generation, scan) => self.disk_usage.finish(generation, *scan),
+                    AppEvent::DirSizesDone(generation) => {
+                        if self.dir_sizes.is_latest(generation) {
+                            self.request_dir_sizes();
+                            resort_by_size(&mut self.explorer);
+                            if let Some(other) = self.other_pane.as_mut() {
+                                resort_by_size(&mut other.explorer);
+                            }
+                        }
+                    },
+                }
+            }
+            if self.bulk_rename_requested {
+                self.bulk_rename_requested = false;
+                if let Err(err) = bulk_rename(self, terminal) {
+                    self.log_panel.set_log(err);
+                }
+            }
+            self.watch_panes();
+            self.request_dir_sizes();
+            self.preview.request(self.explorer.selected_path(), self.include_hidden, self.explorer.sort);
+            terminal.draw(|frame| self.draw(frame)).expect("Unable to draw to the terminal");
+        }
+        Ok(())
+    }
+
+    fn handle_event(&mut self, rec_event: Event) -> Result<(), String> {
+        match rec_event {
+            Event::Key(key_event) => {
+                if key_event.kind == KeyEventKind::Press {
+                    // Clearing log output before handling the operation
+                    self.log_panel.clear_log();
+                    if self.pending_operation.is_some() {
+                        conflict_handle_key(self, key_event);
+                    } else if self.help_shown {
+                        match key_event.code {
+                            KeyCode::Char('q') => self.help_shown = false,
+                            KeyCode::Char('j') | KeyCode::Down => self.help_overview.scroll = if self.help_overview.scroll >= self.help_overview.max_scroll {self.help_overview.max_scroll} else {self.help_overview.scroll + 1},
+                            KeyCode::Char('k') | KeyCode::Up => self.help_overview.scroll = self.help_overview.scroll.saturating_sub(1),
+                            _ => (),
+                        }
+                    } else if self.finder_shown {
+                        match key_event.code {
+                            KeyCode::Esc => {
+                                self.finder.close();
+                                self.finder_shown = false;
+                            },
+                            KeyCode::Enter => finder_handle_enter(self),
+                            _ => self.finder.handle_input(rec_event)?,
+                        }
+                    } else if self.grep_shown {
+                        match key_event.code {
+                            KeyCode::Esc | KeyCode::Char('q') => {
+                                self.grep.stop();
+                                self.grep_shown = false;
+                            },
+                            KeyCode::Enter => grep_handle_enter(self)?,
+                            KeyCode::Char('e') => grep_reveal(self),
+                            _ => self.grep.handle_input(rec_event)?,
+                        }
+                    } else if self.history_shown {
+                        match key_event.code {
+                            KeyCode::Esc | KeyCode::Char('q') => self.history_shown = false,
+                            KeyCode::Enter => {
+                                if let Some(dir_path) = self.history.selected_recent() {
+                                    self.navigate_to(dir_path);
                                 }
-                            } else if self.focus_on == CurrentWidget::Explorer && key_event.code == KeyCode::Delete && key_event.modifiers.contains(KeyModifiers::SHIFT) {
-                                self.log_panel.set_log("Do you want to permenantly delete the item? (Y/N)".to_string());
-                                self.explorer.delete_ongoing = true;
-                            } else if self.focus_on == CurrentWidget::Explorer && self.explorer.delete_ongoing {
-                                match key_event.code {
-                                    KeyCode::Char('y') | KeyCode::Char('Y') => self.explorer.handle_delete()?,
-                                    _ => {}
+                                self.history_shown = false;
+                            },
+                            _ => self.history.handle_input(rec_event)?,
+                        }
+                    } else if self.column_picker.is_some() {
+                        match key_event.code {
+                            KeyCode::Esc | KeyCode::Char('q') => self.column_picker = None,
+                            KeyCode::Enter => apply_columns(self),
+                            _ => {
+                                if let Some(picker) = self.column_picker.as_mut() {
+                                    picker.handle_input(rec_event)?;
                                 }
-                                self.explorer.delete_ongoing = false;
-                            } else {
-                                match key_event.code {
-                                    KeyCode::Tab => {
-                                        focus_toggler(self);
-                                        self.focus_on = self.focus_on.next();
-                                        focus_toggler(self);
-                                    },
-                                    KeyCode::BackTab => {
-                                        focus_toggler(self);
-                                        self.focus_on = self.focus_on.previous();
-                                        focus_toggler(self);
-                                    }
-                                    KeyCode::Enter => {
-                                        match self.focus_on {
-                                            CurrentWidget::PathField => {
-                                                let mut input_path = PathBuf::from(self.path_field.input.value());
-                                                if input_path.exists() {
-                                                    if !input_path.is_dir() {
-                                                        input_path = PathBuf::from(input_path.parent().unwrap_or(Path::new(".")));
-                                                    }
-                                                    self.path_field.set_value(String::from(input_path.to_string_lossy()));
-                                                    self.explorer.refresh(&input_path, self.include_hidden);
-                                                    focus_to(self, CurrentWidget::Explorer);
-                                                }
-                                            },
-                                            CurrentWidget::Explorer => {
-                                                explorer_handle_enter(self)?;
-                                            }
-                                            CurrentWidget::Drives => {
-                                                if let Some(selected_idx) = self.drives.state.selected() {
-                                                    let entry = &self.drives.drives[selected_idx];
-                                                    let dir_path = entry.mount_point.clone();
-                                                    self.path_field.set_value(String::from(dir_path.to_string_lossy()));
-                                                    self.explorer.refresh(&dir_path, self.include_hidden);
-                                                    focus_to(self, CurrentWidget::Explorer);
-                                                } else {
-                                                    self.log_panel.set_log(String::from("Error in retrieving the drives"));
-                                                }
-                                            },
-                                            CurrentWidget::CommandBar => {
-                                                handle_command_enter(self);
-                                            },
-                                            CurrentWidget::QuickAccess => {
-                                                if let Some(selected_idx) = self.quick_access.state.selected() {
-                                                    let entry = &self.quick_access.entries[selected_idx];
-                                                    let dir_path = entry.path.clone();
-                                                    self.path_field.set_value(String::from(dir_path.to_string_lossy()));
-                                                    self.explorer.refresh(&dir_path, self.include_hidden);
-                                                    self.quick_access.state.select(Some(0));
-                                                    focus_to(self, CurrentWidget::Explorer);
-                                                } else {
-                                                    self.log_panel.set_log(String::from("Error in retrieving the Quick Access Files"));
-                                                }
-                                            }
-                                        }
-                                        update_qa_files(self, String::from(PathBuf::from(self.path_field.input.value()).file_name().and_then(|name| name.to_str()).unwrap_or("default")), PathBuf::from(self.path_field.input.value()));
-                                    },
-                                    KeyCode::Char(':') => {
-                                        if self.focus_on == CurrentWidget::CommandBar && self.command.input_mode == InputMode::Editing  || self.focus_on == CurrentWidget::PathField && self.path_field.input_mode == InputMode::Editing {
-                                            self.get_focused_widget().handle_input(rec_event)?;
-                                        } else {
-                                            focus_to(self, CurrentWidget::CommandBar);
-                                            self.command.input_mode = InputMode::Editing;
-                                        }
-                                    },
-                                    KeyCode::Char('a') => {
-                                        if self.focus_on == CurrentWidget::CommandBar && self.command.input_mode == InputMode::Editing  || self.focus_on == CurrentWidget::PathField && self.path_field.input_mode == InputMode::Editing {
-                                            self.get_focused_widget().handle_input(rec_event)?;
-                                        } else {
-                                                focus_to(self, CurrentWidget::PathField);
-                                                self.path_field.input_mode = InputMode::Editing;
-                                        }
-                                    },
-                                    KeyCode::Backspace => {
-                                        if self.focus_on == CurrentWidget::CommandBar && self.command.input_mode == InputMode::Editing  || self.focus_on == CurrentWidget::PathField && self.path_field.input_mode == InputMode::Editing {
-                                            self.get_focused_widget().handle_input(rec_event)?;
-                                        } else {
-                                            let current_dir = PathBuf::from(self.path_field.input.value());
-                                            if let Some(parent_dir) = current_dir.parent() {
-                                                let parent_dir_str = String::from(parent_dir.to_string_lossy());
-                                                self.path_field.set_value(parent_dir_str);
-                                                self.explorer.refresh(&PathBuf::from(parent_dir), self.include_hidden);
-                                            } else {
-                                                self.log_panel.set_log(String::from("Couldn't find the parent directory"));
-                                            }
-                                        }
-                                    },
-                                    KeyCode::Char('h') => {
-                                        if self.focus_on == CurrentWidget::CommandBar && self.command.input_mode == InputMode::Editing  || self.focus_on == CurrentWidget::PathField && self.path_field.input_mode == InputMode::Editing {
-                                            self.get_focused_widget().handle_input(rec_event)?;
-                                        } else {
-                                            self.help_shown = true;
-                                        }
-                                    },
-                                    KeyCode::Char('q') => {
-                                        if self.focus_on == CurrentWidget::CommandBar && self.command.input_mode == InputMode::Editing  || self.focus_on == CurrentWidget::PathField && self.path_field.input_mode == InputMode::Editing {
-                                            self.get_focused_widget().handle_input(rec_event)?;
-                                        } else {
-                                            self.exit_app();
-                                        }
-                                    },
-                                    _ => self.get_focused_widget().handle_input(rec_event)?,
+                            },
+                        }
+                    } else if self.chmod_editor.is_some() {
+                        match key_event.code {
+                            KeyCode::Esc | KeyCode::Char('q') => self.chmod_editor = None,
+                            KeyCode::Enter => apply_chmod(self),
+                            _ => {
+                                if let Some(editor) = self.chmod_editor.as_mut() {
+                                    editor.handle_input(rec_event)?;
                                 }
+                            },
+                        }
+                    } else if self.disk_usage_shown {
+                        if self.disk_usage.confirm_delete {
+                            self.disk_usage.confirm_delete = false;
+                            if matches!(key_event.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                                disk_usage_delete(self);
                             }
                         } else {
-                            self.get_focused_widget().handle_input(rec_event)?;
+                            match key_event.code {
+                                KeyCode::Esc | KeyCode::Char('q') => {
+                                    self.disk_usage.stop();
+                                    self.disk_usage_shown = false;
+                                },
+                                KeyCode::Char('d') => self.disk_usage.confirm_delete = self.disk_usage.selected().is_some(),
+                                _ => self.disk_usage.handle_input(rec_event)?,
+                            }
                         }
-                    },
-                    _ => self.get_focused_widget().handle_input(rec_event)?,
+                    } else if self.jobs_shown {
+                        match key_event.code {
+                            KeyCode::Esc | KeyCode::Char('q') => self.jobs_shown = false,
+                            _ => self.jobs.handle_input(rec_event)?,
+                        }
+                    } else if self.focus_on == CurrentWidget::Explorer && self.explorer.filter.editing {
+                        // Every key goes to the filter while it is being typed in
+                        self.explorer.handle_input(rec_event)?;
+                    } else if self.focus_on == CurrentWidget::Explorer && key_event.code == KeyCode::Delete && key_event.modifiers.contains(KeyModifiers::SHIFT) {
+                        let count = self.explorer.selected_paths().len();
+                        if count > 1 {
+                            self.log_panel.set_log(format!("Do you want to permenantly delete the {count} marked items? This can't be undone. (Y/N)"));
+                        } else {
+                            self.log_panel.set_log("Do you want to permenantly delete the item? This can't be undone. (Y/N)".to_string());
+                        }
+                        self.explorer.delete_ongoing = true;
+                    } else if self.focus_on == CurrentWidget::Explorer && self.explorer.delete_ongoing {
+                        match key_event.code {
+                            KeyCode::Char('y') | KeyCode::Char('Y') => self.explorer.handle_delete(&mut self.jobs),
+                            _ => {}
+                        }
+                        self.explorer.delete_ongoing = false;
+                    } else {
+                        match key_event.code {
+                            // In the dual-pane mode, Tab goes through the left pane, then the right pane
+                            KeyCode::Tab if self.focus_on == CurrentWidget::Explorer && self.other_pane.is_some() && self.left_pane_active => switch_pane(self),
+                            KeyCode::BackTab if self.focus_on == CurrentWidget::Explorer && self.other_pane.is_some() && !self.left_pane_active => switch_pane(self),
+                            KeyCode::Tab => {
+                                focus_toggler(self);
+                                self.focus_on = self.focus_on.next();
+                                focus_toggler(self);
+                            },
+                            KeyCode::BackTab => {
+                                focus_toggler(self);
+                                self.focus_on = self.focus_on.previous();
+                                focus_toggler(self);
+                            }
+                            KeyCode::F(5) if self.focus_on == CurrentWidget::Explorer => transfer_to_other_pane(self, false)?,
+                            KeyCode::F(6) if self.focus_on == CurrentWidget::Explorer => transfer_to_other_pane(self, true)?,
+                            KeyCode::Enter => self.handle_enter()?,
+                            KeyCode::Char(':') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    focus_to(self, CurrentWidget::CommandBar);
+                                    self.command.input_mode = InputMode::Editing;
+                                }
+                            },
+                            KeyCode::Char('a') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                        focus_to(self, CurrentWidget::PathField);
+                                        self.path_field.input_mode = InputMode::Editing;
+                                }
+                            },
+                            KeyCode::Backspace => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    let current_dir = PathBuf::from(self.path_field.input.value());
+                                    if let Some(parent_dir) = current_dir.parent() {
+                                        self.navigate_to(PathBuf::from(parent_dir));
+                                        // Keeping the cursor on the directory we came out of
+                                        if let Some(dir_name) = current_dir.file_name() {
+                                            self.explorer.select_name(&dir_name.to_string_lossy());
+                                        }
+                                    } else {
+                                        self.log_panel.set_log(String::from("Couldn't find the parent directory"));
+                                    }
+                                }
+                            },
+                            KeyCode::Char('H') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.go_back();
+                                }
+                            },
+                            KeyCode::Char('L') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.go_forward();
+                                }
+                            },
+                            KeyCode::Left if key_event.modifiers.contains(KeyModifiers::ALT) && !self.is_typing() => self.go_back(),
+                            KeyCode::Right if key_event.modifiers.contains(KeyModifiers::ALT) && !self.is_typing() => self.go_forward(),
+                            KeyCode::Char('R') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.history.state.select(Some(0));
+                                    self.history_shown = true;
+                                }
+                            },
+                            KeyCode::Char('c') | KeyCode::Char('x') if self.focus_on == CurrentWidget::Explorer => {
+                                self.explorer.handle_copy(&mut self.clipboard, key_event.code == KeyCode::Char('x'), false);
+                            },
+                            KeyCode::Char('C') if self.focus_on == CurrentWidget::Explorer => {
+                                self.explorer.handle_copy(&mut self.clipboard, false, true);
+                            },
+                            KeyCode::Delete if self.focus_on == CurrentWidget::Explorer => {
+                                let trashed = self.explorer.move_item_to_trash()?;
+                                if !trashed.is_empty() {
+                                    self.undo.record(Operation::Trash(trashed));
+                                }
+                            },
+                            KeyCode::Char('E') if self.focus_on == CurrentWidget::Explorer => self.bulk_rename_requested = true,
+                            KeyCode::Char('P') if self.focus_on == CurrentWidget::Explorer => open_chmod_editor(self),
+                            KeyCode::Char('V') if self.focus_on == CurrentWidget::Explorer => open_column_picker(self),
+                            KeyCode::Char('g') if self.focus_on == CurrentWidget::Explorer => {
+                                if let Err(err) = jump_to_link_target(self) {
+                                    self.log_panel.set_log(err);
+                                }
+                            },
+                            KeyCode::Char('u') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    undo(self);
+                                }
+                            },
+                            KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) && !self.is_typing() => redo(self),
+                            KeyCode::Char('v') if self.focus_on == CurrentWidget::Explorer => {
+                                paste_clipboard(self);
+                            },
+                            KeyCode::Char('t') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    new_tab(self);
+                                }
+                            },
+                            KeyCode::Char('T') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    close_tab(self);
+                                }
+                            },
+                            KeyCode::Char(']') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    next_tab(self);
+                                }
+                            },
+                            KeyCode::Char('[') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    previous_tab(self);
+                                }
+                            },
+                            KeyCode::Char('J') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.jobs_shown = true;
+                                }
+                            },
+                            KeyCode::Char('z') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.dir_sizes.toggle();
+                                    self.explorer.reload();
+                                    if let Some(other) = self.other_pane.as_mut() {
+                                        other.explorer.reload();
+                                    }
+                                    self.log_panel.set_log(format!("Directory sizes: {}", self.dir_sizes.mode.label()));
+                                }
+                            },
+                            KeyCode::Char('w') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    toggle_dual_pane(self);
+                                }
+                            },
+                            KeyCode::Char('h') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.help_shown = true;
+                                }
+                            },
+                            KeyCode::Char('f') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.finder.open(self.explorer.root_path.clone(), self.include_hidden, self.event_tx.clone());
+                                    self.finder_shown = true;
+                                }
+                            },
+                            KeyCode::Char('p') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.preview.toggle();
+                                }
+                            },
+                            KeyCode::Char('q') => {
+                                if self.is_typing() {
+                                    self.get_focused_widget().handle_input(rec_event)?;
+                                } else {
+                                    self.exit_app();
+                                }
+                            },
+                            _ => self.get_focused_widget().handle_input(rec_event)?,
+                        }
+                    }
+                } else {
+                    self.get_focused_widget().handle_input(rec_event)?;
                 }
-            }
-            terminal.draw(|frame| self.draw(frame)).expect("Unable to draw to the terminal");
+            },
+            Event::Mouse(mouse_event) => handle_mouse(self, mouse_event)?,
+            _ => self.get_focused_widget().handle_input(rec_event)?,
         }
         Ok(())
     }
@@ -248,6 +550,13 @@ impl App {
         ]);
         let vertical_split_areas = vertical_layout.split(frame.area());
 
+        // The tab bar is only shown when more than one tab is open
+        let [tab_bar_area, main_area] = Layout::vertical([
+            Constraint::Length(if self.tabs.len() > 1 { 1 } else { 0 }),
+            Constraint::Min(0),
+        ])
+        .areas(vertical_split_areas[1]);
+
         let [title, path_bar] = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
@@ -256,7 +565,30 @@ impl App {
         let [sidebar, explorer_cont_area] = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
-            .areas(vertical_split_areas[1]);
+            .areas(main_area);
+
+        let [explorer_cont_area, preview_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(if self.preview.shown {
+                [Constraint::Percentage(60), Constraint::Percentage(40)]
+            } else {
+                [Constraint::Percentage(100), Constraint::Length(0)]
+            })
+            .areas(explorer_cont_area);
+
+        let [left_pane_area, right_pane_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(if self.other_pane.is_some() {
+                [Constraint::Percentage(50), Constraint::Percentage(50)]
+            } else {
+                [Constraint::Percentage(100), Constraint::Length(0)]
+            })
+            .areas(explorer_cont_area);
+        let (explorer_cont_area, other_pane_area) = if self.left_pane_active {
+            (left_pane_area, right_pane_area)
+        } else {
+            (right_pane_area, left_pane_area)
+        };
 
         let [explorer_area, explorer_scroll_bar] = Layout::default()
             .direction(Direction::Horizontal)
@@ -284,18 +616,59 @@ impl App {
         // Rendering the Command area
         self.command.render_input(frame, vertical_split_areas[2]);
 
+        // Rendering the tab bar
+        if self.tabs.len() > 1 {
+            render_tab_bar(self, frame, tab_bar_area);
+        }
+
         // Rendering the explorer area
         self.explorer.create_explorer_table(frame, explorer_area);
         self.explorer.render_scrollbar(frame, explorer_scroll_bar);
 
+        // Rendering the other pane of the dual-pane mode
+        if let Some(other) = self.other_pane.as_mut() {
+            let [other_area, other_scroll_bar] = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(97), Constraint::Percentage(3)])
+                .areas(other_pane_area);
+            other.explorer.create_explorer_table(frame, other_area);
+            other.explorer.render_scrollbar(frame, other_scroll_bar);
+        }
+
+        // Rendering the preview pane
+        if self.preview.shown {
+            self.preview.render(frame, preview_area);
+        }
+
         // Rendering the drives area
         self.drives.create_drives_table(frame, drive_area);
 
         // Rendering the quick access area
         self.quick_access.create_qa_entries_table(frame, quick_access_area);
 
-        // Rendering the Log Panel
-        self.log_panel.render_widget(frame, vertical_split_areas[3]);      
+        // Rendering the Log Panel, with the progress of the running job on its right
+        let job_summary = self.jobs.summary();
+        let [log_area, job_status_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(job_summary.as_ref().map_or(0, |summary| summary.len() as u16))])
+            .areas(vertical_split_areas[3]);
+        self.log_panel.render_widget(frame, log_area);
+        if let Some(summary) = job_summary {
+            frame.render_widget(Paragraph::new(summary).cyan(), job_status_area);
+        }
+
+        // Previewing a `rename` command while it is typed
+        if self.is_typing() && self.focus_on == CurrentWidget::CommandBar && let Some(args) = self.command.value().strip_prefix("rename ") {
+            let command_area = vertical_split_areas[2];
+            let height = vertical_split_areas[1].height / 2;
+            let rename_preview_area = Rect {
+                x: command_area.x,
+                y: command_area.y.saturating_sub(height),
+                width: command_area.width,
+                height,
+            };
+            render_rename_preview(args, &self.explorer, &self.explorer.color_theme, frame, rename_preview_area);
+        }
 
         // Conditionally rendering the help overview
         if self.help_shown {
@@ -310,6 +683,264 @@ impl App {
 
             self.help_overview.render(help_popup_area, frame.buffer_mut());
         }
+
+        // Conditionally rendering the file finder
+        if self.finder_shown {
+            let area = frame.area();
+
+            let finder_popup_area = Rect {
+                x: area.width / 10,
+                y: area.height / 10,
+                width: (0.8 * area.width as f32) as u16,
+                height: (0.8 * area.height as f32) as u16,
+            };
+
+            self.finder.render(frame, finder_popup_area);
+        }
+
+        // Conditionally rendering the grep results
+        if self.grep_shown {
+            let area = frame.area();
+
+            let grep_popup_area = Rect {
+                x: area.width / 10,
+                y: area.height / 10,
+                width: (0.8 * area.width as f32) as u16,
+                height: (0.8 * area.height as f32) as u16,
+            };
+
+            self.grep.render(frame, grep_popup_area);
+        }
+
+        // Conditionally rendering the disk usage view
+        if self.disk_usage_shown {
+            let area = frame.area();
+
+            let disk_usage_popup_area = Rect {
+                x: area.width / 10,
+                y: area.height / 10,
+                width: (0.8 * area.width as f32) as u16,
+                height: (0.8 * area.height as f32) as u16,
+            };
+
+            self.disk_usage.render(frame, disk_usage_popup_area);
+        }
+
+        // Conditionally rendering the recent locations
+        if self.history_shown {
+            let area = frame.area();
+
+            let history_popup_area = Rect {
+                x: area.width / 5,
+                y: area.height / 5,
+                width: (0.6 * area.width as f32) as u16,
+                height: (0.6 * area.height as f32) as u16,
+            };
+
+            self.history.render(frame, history_popup_area);
+        }
+
+        // Conditionally rendering the conflict prompt
+        if let Some(operation) = &self.pending_operation {
+            let area = frame.area();
+
+            let conflict_popup_area = Rect {
+                x: area.width / 5,
+                y: area.height / 3,
+                width: (0.6 * area.width as f32) as u16,
+                height: 10.min(area.height),
+            };
+
+            operation.render(frame, conflict_popup_area);
+        }
+
+        // Conditionally rendering the chmod popup
+        if let Some(editor) = &self.chmod_editor {
+            let area = frame.area();
+
+            let chmod_popup_area = Rect {
+                x: area.width.saturating_sub(64) / 2,
+                y: area.height / 3,
+                width: 64.min(area.width),
+                height: 10.min(area.height),
+            };
+
+            editor.render(frame, chmod_popup_area);
+        }
+
+        // Conditionally rendering the column picker
+        if let Some(picker) = self.column_picker.as_mut() {
+            let area = frame.area();
+
+            let picker_popup_area = Rect {
+                x: area.width.saturating_sub(72) / 2,
+                y: area.height.saturating_sub(picker.height()) / 2,
+                width: 72.min(area.width),
+                height: picker.height().min(area.height),
+            };
+
+            picker.render(frame, picker_popup_area);
+        }
+
+        // Conditionally rendering the jobs panel
+        if self.jobs_shown {
+            let area = frame.area();
+
+            let jobs_popup_area = Rect {
+                x: area.width / 10,
+                y: area.height / 5,
+                width: (0.8 * area.width as f32) as u16,
+                height: (0.6 * area.height as f32) as u16,
+            };
+
+            self.jobs.render(frame, jobs_popup_area);
+        }
+    }
+
+    /// Moves the Explorer to `dir_path` and records the location being left in the history
+    fn navigate_to(&mut self, dir_path: PathBuf) {
+        if dir_path != self.explorer.root_path {
+            let left = self.current_location();
+            self.history.push(left);
+        }
+        self.open_dir(&dir_path);
+    }
+
+    // Moves the Explorer to `dir_path` without touching the back / forward stacks
+    fn open_dir(&mut self, dir_path: &Path) {
+        self.path_field.set_value(String::from(dir_path.to_string_lossy()));
+        self.explorer.refresh(&dir_path.to_path_buf(), self.include_hidden);
+        self.history.visit(dir_path);
+    }
+
+    fn current_location(&self) -> HistoryEntry {
+        HistoryEntry {
+            path: self.explorer.root_path.clone(),
+            selected: self.explorer.selected_name(),
+        }
+    }
+
+    fn go_back(&mut self) {
+        let current = self.current_location();
+        match self.history.back(current) {
+            Some(entry) => self.restore_location(entry),
+            None => self.log_panel.set_log(String::from("No previous location in the history")),
+        }
+    }
+
+    fn go_forward(&mut self) {
+        let current = self.current_location();
+        match self.history.forward(current) {
+            Some(entry) => self.restore_location(entry),
+            None => self.log_panel.set_log(String::from("No next location in the history")),
+        }
+    }
+
+    fn restore_location(&mut self, entry: HistoryEntry) {
+        self.open_dir(&entry.path);
+        if let Some(name) = entry.selected {
+            self.explorer.select_name(&name);
+        }
+    }
+
+    // Reports the end of a job and shows its result in the Explorers
+    fn handle_job_done(&mut self, id: u64, outcome: JobOutcome) {
+        if let Some(job) = self.jobs.finish(id, outcome) {
+            self.undo.record_job(job);
+            let msg = match &job.status {
+                JobStatus::Failed(_) => format!("{}: {} item(s) failed, press J to see them", job.kind.label(), job.failures.len()),
+                JobStatus::Cancelled => format!("{} cancelled", job.kind.label()),
+                _ if job.kind == JobKind::Chmod => format!("Changed the permissions of {} item(s)", job.mode_changes.len()),
+                _ if job.kind == JobKind::Chown => format!("Changed the owner of {} item(s)", job.owner_changes.len()),
+                _ => format!("{} of {} item(s) finished", job.kind.label(), job.items.len()),
+            };
+            self.log_panel.set_log(msg);
+        }
+        self.explorer.reload();
+        if let Some(other) = self.other_pane.as_mut() {
+            other.explorer.reload();
+        }
+    }
+
+    // Refreshes the panes showing the directory, keeping their cursor on the same entry
+    fn handle_dir_changed(&mut self, dir_path: PathBuf) {
+        if self.explorer.root_path == dir_path {
+            self.explorer.reload();
+        }
+        if let Some(other) = self.other_pane.as_mut() && other.explorer.root_path == dir_path {
+            other.explorer.reload();
+        }
+    }
+
+    // Fills in the sizes of the directories shown in the panes
+    fn request_dir_sizes(&mut self) {
+        let mut explorers: Vec<&mut Explorer> = vec![&mut self.explorer];
+        if let Some(other) = self.other_pane.as_mut() {
+            explorers.push(&mut other.explorer);
+        }
+        self.dir_sizes.request(&mut explorers);
+    }
+
+    // Follows the directories shown in the panes with the watcher
+    fn watch_panes(&mut self) {
+        let mut dir_paths = vec![self.explorer.root_path.clone()];
+        if let Some(other) = &self.other_pane && other.explorer.root_path != self.explorer.root_path {
+            dir_paths.push(other.explorer.root_path.clone());
+        }
+        if let Err(err) = self.watcher.watch(dir_paths) {
+            self.log_panel.set_log(err);
+        }
+    }
+
+    /// Acts on the item selected in the focused widget -> Bound to `Enter` and to a double click
+    fn handle_enter(&mut self) -> Result<(), String> {
+        match self.focus_on {
+            CurrentWidget::PathField => {
+                let mut input_path = PathBuf::from(self.path_field.input.value());
+                if input_path.exists() {
+                    if !input_path.is_dir() {
+                        input_path = PathBuf::from(input_path.parent().unwrap_or(Path::new(".")));
+                    }
+                    self.navigate_to(input_path);
+                    focus_to(self, CurrentWidget::Explorer);
+                }
+            },
+            CurrentWidget::Explorer => {
+                explorer_handle_enter(self)?;
+            }
+            CurrentWidget::Drives => {
+                if let Some(selected_idx) = self.drives.state.selected() {
+                    let entry = &self.drives.drives[selected_idx];
+                    let dir_path = entry.mount_point.clone();
+                    self.navigate_to(dir_path);
+                    focus_to(self, CurrentWidget::Explorer);
+                } else {
+                    self.log_panel.set_log(String::from("Error in retrieving the drives"));
+                }
+            },
+            CurrentWidget::CommandBar => {
+                handle_command_enter(self);
+            },
+            CurrentWidget::QuickAccess => {
+                if let Some(selected_idx) = self.quick_access.state.selected() {
+                    let entry = &self.quick_access.entries[selected_idx];
+                    let dir_path = entry.path.clone();
+                    self.navigate_to(dir_path);
+                    self.quick_access.state.select(Some(0));
+                    focus_to(self, CurrentWidget::Explorer);
+                } else {
+                    self.log_panel.set_log(String::from("Error in retrieving the Quick Access Files"));
+                }
+            }
+        }
+        update_qa_files(self, String::from(PathBuf::from(self.path_field.input.value()).file_name().and_then(|name| name.to_str()).unwrap_or("default")), PathBuf::from(self.path_field.input.value()));
+        Ok(())
+    }
+
+    /// Whether the keys typed are registered as text in the Command bar or the Path Field
+    fn is_typing(&self) -> bool {
+        self.focus_on == CurrentWidget::CommandBar && self.command.input_mode == InputMode::Editing
+            || self.focus_on == CurrentWidget::PathField && self.path_field.input_mode == InputMode::Editing
     }
 
     fn get_focused_widget(&mut self) -> &mut dyn HandlesInput {
@@ -336,48 +967,108 @@ impl App {
 fn main() {
     let cli = CLI::parse();
 
-    let mut current_path = match cli.path {
-        Some(res) => res,
-        None => current_dir().unwrap_or(PathBuf::from(".")),
+    let (left_path, right_path) = match cli.dual {
+        Some(dual_paths) if dual_paths.len() == 2 => (Some(dual_paths[0].clone()), Some(dual_paths[1].clone())),
+        _ => (cli.path, None),
     };
 
-    current_path = if current_path.is_dir() { current_path } else { PathBuf::from(&current_path.parent().unwrap_or(Path::new(".")))};
-
-    current_path = std::path::absolute(current_path.clone()).unwrap_or(current_path);
+    let current_path = start_dir(left_path.unwrap_or(current_dir().unwrap_or(PathBuf::from("."))));
 
     let mut terminal = ratatui::init();
+    enable_mouse_capture();
 
     let color_theme = get_color_theme(cli.color_theme_path);
 
+    let settings = read_settings(cli.settings_path.clone());
+
+    let (tx, rx) = mpsc::channel::<AppEvent>();
+
+    let mut explorer = Explorer::new(&current_path, cli.file_options_path,cli.include_hidden, settings.sort_options(), color_theme.clone());
+    explorer.columns = settings.columns.clone();
+    explorer.nerd_font_icons = settings.nerd_font_icons;
+    let first_tab = Tab {
+        explorer: explorer.clone(),
+        history: History::new(&current_path, color_theme.clone()),
+    };
+
     let mut app: App = App {
         exit: false,
         quick_access: QuickAccess::new(color_theme.clone()),
         path_field: PathField::new(&current_path, color_theme.clone()),
         command: Command::new(color_theme.clone()),
-        explorer: Explorer::new(&current_path, cli.file_options_path,cli.include_hidden, color_theme.clone()),
+        explorer,
         drives: Drives::new(color_theme.clone()),
         focus_on: CurrentWidget::Explorer,
         include_hidden: cli.include_hidden,
         log_panel: LogPanel::new(),
         help_overview: HelpOverview::new(color_theme.clone()),
         help_shown: false,
+        finder: Finder::new(color_theme.clone()),
+        finder_shown: false,
+        grep: GrepSearch::new(color_theme.clone()),
+        grep_shown: false,
+        preview: Preview::new(tx.clone(), color_theme.clone()),
+        history: History::new(&current_path, color_theme.clone()),
+        history_shown: false,
+        tabs: vec![first_tab],
+        active_tab: 0,
+        clipboard: Clipboard::default(),
+        other_pane: None,
+        jobs: Jobs::new(tx.clone(), color_theme.clone()),
+        jobs_shown: false,
+        pending_operation: None,
+        conflict_policy: settings.conflict_policy,
+        undo: UndoHistory::default(),
+        bulk_rename_requested: false,
+        input_paused: Arc::new(AtomicBool::new(false)),
+        last_click: None,
+        watcher: DirWatcher::new(tx.clone()),
+        dir_sizes: DirSizes::new(settings.directory_sizes, tx.clone()),
+        disk_usage: DiskUsage::new(color_theme.clone()),
+        disk_usage_shown: false,
+        chmod_editor: None,
+        column_picker: None,
+        settings_path: settings_path(cli.settings_path),
+        left_pane_active: true,
+        event_tx: tx.clone(),
     };
 
+    if let Some(right_path) = right_path {
+        open_dual_pane(&mut app, start_dir(right_path));
+    }
+    if !app.explorer.file_open_options.errors.is_empty() {
+        app.log_panel.set_log(app.explorer.file_open_options.errors.join(", "));
+    }
+
     // Spawning a input thread
-    let (tx, rx) = mpsc::channel::<Event>();
-    thread::spawn(move || handle_input_events(tx.clone()));
+    let input_paused = Arc::clone(&app.input_paused);
+    thread::spawn(move || handle_input_events(tx.clone(), input_paused));
 
     if let Err(err)  = app.run(&mut terminal, rx) {
         app.log_panel.set_log(err);
     }
 
+    disable_mouse_capture();
     ratatui::restore();
 }
 
-fn handle_input_events(tx: mpsc::Sender<Event>) {
+// The directory to start in -> The parent is taken when a file is given
+fn start_dir(path: PathBuf) -> PathBuf {
+    let dir_path = if path.is_dir() { path } else { PathBuf::from(&path.parent().unwrap_or(Path::new(".")))};
+    std::path::absolute(dir_path.clone()).unwrap_or(dir_path)
+}
+
+fn handle_input_events(tx: mpsc::Sender<AppEvent>, paused: Arc<AtomicBool>) {
     loop {
+        // Polling with a timeout, so that a pause is noticed while no key is pressed
+        if paused.load(Ordering::Relaxed) || !crossterm::event::poll(Duration::from_millis(20)).unwrap_or(false) {
+            if paused.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(20));
+            }
+            continue;
+        }
         if let Ok(rec_event) = crossterm::event::read() {
-            if let Ok(_suc) = tx.send(rec_event) {
+            if let Ok(_suc) = tx.send(AppEvent::Input(rec_event)) {
                 // Success of transmission
             }
         }
@@ -0,0 +1,132 @@
+use std::{
+    io::stdout,
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture, Event, MouseButton, MouseEvent, MouseEventKind},
+    execute,
+};
+use ratatui::layout::Position;
+
+use crate::{App, CurrentWidget, dependencies::{HandlesInput, InputMode, focus_to, table_row_at}, dual_pane::switch_pane};
+
+/// Two clicks on the same cell within this time make a double click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+pub fn enable_mouse_capture() {
+    let _ = execute!(stdout(), EnableMouseCapture);
+}
+
+pub fn disable_mouse_capture() {
+    let _ = execute!(stdout(), DisableMouseCapture);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MouseTarget {
+    Widget(CurrentWidget),
+    /// The pane that isn't active in the dual-pane mode
+    OtherPane,
+}
+
+// The widget drawn at `position`
+fn target_at(app: &App, position: Position) -> Option<MouseTarget> {
+    if app.explorer.area.contains(position) || app.explorer.scrollbar_area.contains(position) {
+        return Some(MouseTarget::Widget(CurrentWidget::Explorer));
+    }
+    if let Some(other) = &app.other_pane
+        && (other.explorer.area.contains(position) || other.explorer.scrollbar_area.contains(position))
+    {
+        return Some(MouseTarget::OtherPane);
+    }
+    [
+        (app.drives.area, CurrentWidget::Drives),
+        (app.quick_access.area, CurrentWidget::QuickAccess),
+        (app.path_field.area, CurrentWidget::PathField),
+        (app.command.area, CurrentWidget::CommandBar),
+    ]
+    .into_iter()
+    .find(|(area, _widget)| area.contains(position))
+    .map(|(_area, widget)| MouseTarget::Widget(widget))
+}
+
+// Popups take all the input while they are shown
+fn popup_shown(app: &App) -> bool {
+    app.pending_operation.is_some() || app.help_shown || app.finder_shown || app.grep_shown || app.history_shown || app.jobs_shown || app.disk_usage_shown || app.chmod_editor.is_some() || app.column_picker.is_some()
+}
+
+// Whether this click follows a click on the same cell closely enough to make a double click
+fn is_double_click(app: &mut App, position: Position) -> bool {
+    let now = Instant::now();
+    let double = app
+        .last_click
+        .is_some_and(|(time, last_position)| last_position == position && now.duration_since(time) <= DOUBLE_CLICK_TIME);
+    // A third click starts over
+    app.last_click = if double { None } else { Some((now, position)) };
+    double
+}
+
+// Whether the click landed on the row selected in the table of `widget`, and not on its header or scrollbar
+fn on_selected_row(app: &App, widget: CurrentWidget, position: Position) -> bool {
+    let (area, state) = match widget {
+        CurrentWidget::Explorer => (app.explorer.area, &app.explorer.state),
+        CurrentWidget::Drives => (app.drives.area, &app.drives.state),
+        CurrentWidget::QuickAccess => (app.quick_access.area, &app.quick_access.state),
+        _ => return false,
+    };
+    table_row_at(area, state.offset(), position).is_some_and(|idx| state.selected() == Some(idx))
+}
+
+/// Clicks focus the widget under the mouse and select the row clicked, a double click acts as `Enter`.
+/// The wheel scrolls the widget under the mouse without moving the focus.
+pub fn handle_mouse(app: &mut App, mouse_event: MouseEvent) -> Result<(), String> {
+    if popup_shown(app) {
+        return Ok(());
+    }
+    let position = Position::new(mouse_event.column, mouse_event.row);
+    match mouse_event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let Some(target) = target_at(app, position) else {
+                return Ok(());
+            };
+            let double_click = is_double_click(app, position);
+            let widget = match target {
+                MouseTarget::OtherPane => {
+                    switch_pane(app);
+                    CurrentWidget::Explorer
+                },
+                MouseTarget::Widget(widget) => widget,
+            };
+            if app.focus_on != widget {
+                focus_to(app, widget);
+            }
+            match widget {
+                CurrentWidget::PathField => app.path_field.input_mode = InputMode::Editing,
+                CurrentWidget::CommandBar => app.command.input_mode = InputMode::Editing,
+                _ => {
+                    app.get_focused_widget().handle_input(Event::Mouse(mouse_event))?;
+                    if double_click && on_selected_row(app, widget, position) {
+                        app.handle_enter()?;
+                    }
+                },
+            }
+        },
+        // Dragging the scrollbar goes on while the mouse leaves it
+        MouseEventKind::Drag(MouseButton::Left) | MouseEventKind::Up(MouseButton::Left) => {
+            app.get_focused_widget().handle_input(Event::Mouse(mouse_event))?;
+        },
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => match target_at(app, position) {
+            Some(MouseTarget::Widget(CurrentWidget::Explorer)) => app.explorer.handle_input(Event::Mouse(mouse_event))?,
+            Some(MouseTarget::Widget(CurrentWidget::Drives)) => app.drives.handle_input(Event::Mouse(mouse_event))?,
+            Some(MouseTarget::Widget(CurrentWidget::QuickAccess)) => app.quick_access.handle_input(Event::Mouse(mouse_event))?,
+            Some(MouseTarget::OtherPane) => {
+                if let Some(other) = app.other_pane.as_mut() {
+                    other.explorer.handle_input(Event::Mouse(mouse_event))?;
+                }
+            },
+            _ => {},
+        },
+        _ => {},
+    }
+    Ok(())
+}
@@ -1,45 +1,107 @@
 use std::{fs, path::Path, process::Command};
 use std::path::PathBuf;
-use serde::{Deserialize, Serialize};
-use toml::de::Error;
+use glob::Pattern;
+use serde::Deserialize;
+use toml::{Table, Value};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+// A matcher of a rule takes one value or a list of them
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn values(self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+// A `[[rule]]` table of file_options.toml
+#[derive(Deserialize, Debug, Clone)]
+struct RuleConfig {
+    ext: Option<OneOrMany>,
+    glob: Option<OneOrMany>,
+    mime: Option<OneOrMany>,
+    command: String,
+}
+
+/// Opens the files it matches with its command. Each of the extensions, file name globs and MIME types
+/// given must have one matching. A rule without any of them matches every file.
+#[derive(Debug, Clone)]
+pub struct OpenRule {
+    /// Lowercase, without the dot
+    extensions: Vec<String>,
+    globs: Vec<Pattern>,
+    mimes: Vec<Pattern>,
+    pub command: String,
+}
+
+impl OpenRule {
+    fn from_config(config: RuleConfig) -> Result<OpenRule, String> {
+        let patterns = |values: Option<OneOrMany>| -> Result<Vec<Pattern>, String> {
+            values.map(OneOrMany::values).unwrap_or_default().iter()
+                .map(|value| Pattern::new(value).map_err(|err| format!("Invalid pattern {value}: {err}")))
+                .collect()
+        };
+        Ok(OpenRule {
+            extensions: config.ext.map(OneOrMany::values).unwrap_or_default().iter()
+                .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                .collect(),
+            globs: patterns(config.glob)?,
+            mimes: patterns(config.mime)?,
+            command: config.command,
+        })
+    }
+
+    fn matches(&self, file: &Path) -> bool {
+        let name = file.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        // What follows the last dot, so that `.gitignore` has the `gitignore` extension
+        let ext = name.rsplit_once('.').map(|(_stem, ext)| ext.to_lowercase());
+        (self.extensions.is_empty() || ext.is_some_and(|ext| self.extensions.contains(&ext)))
+            && (self.globs.is_empty() || self.globs.iter().any(|glob| glob.matches(&name)))
+            && (self.mimes.is_empty() || mime_guess::from_path(file).iter_raw().any(|mime| self.mimes.iter().any(|pattern| pattern.matches(mime))))
+    }
+}
+
+/// The rules of file_options.toml, in their order -> The first one matching a file opens it
+#[derive(Debug, Clone, Default)]
 pub struct FileOptions {
-    txt: Option<String>,
-    pdf: Option<String>,
-    mp4: Option<String>,
-    mp3: Option<String>,
-    c: Option<String>,
-    cpp: Option<String>,
-    rs: Option<String>,
-    png: Option<String>,
-    jpg: Option<String>,
-    svg: Option<String>,
-    sh: Option<String>,
-    toml: Option<String>,
-    html: Option<String>,
-    css: Option<String>,
-    js: Option<String>,
-    jsx: Option<String>,
-    ts: Option<String>,
-    tsx: Option<String>,
-    py: Option<String>,
-    md: Option<String>,
-    gitignore: Option<String>,
-    rb: Option<String>,
-    java: Option<String>,
-    kt: Option<String>,
-    json: Option<String>,
-    zig: Option<String>,
-    odin: Option<String>,
-    mkv: Option<String>,
-    avi: Option<String>,
-    odt: Option<String>,
-    ods: Option<String>,
-    odp: Option<String>,
-    odg: Option<String>,
-    jpeg: Option<String>,
-    gif: Option<String>,
+    pub rules: Vec<OpenRule>,
+    /// Why some of the rules were left out
+    pub errors: Vec<String>,
+}
+
+// The `[[rule]]` tables come first. The older flat `ext = "command"` keys follow as rules of their own.
+fn parse_file_options(contents: &str) -> Result<FileOptions, String> {
+    let table: Table = toml::from_str(contents).map_err(|err| err.to_string())?;
+    let mut options = FileOptions::default();
+    let mut legacy: Vec<OpenRule> = vec![];
+    for (key, value) in table {
+        match (key.as_str(), value) {
+            ("rule", Value::Array(rules)) => {
+                for rule in rules {
+                    match rule.try_into::<RuleConfig>().map_err(|err| err.to_string()).and_then(OpenRule::from_config) {
+                        Ok(rule) => options.rules.push(rule),
+                        Err(err) => options.errors.push(format!("Skipped a rule of file_options.toml: {}", err.trim())),
+                    }
+                }
+            },
+            (_, Value::String(command)) => legacy.push(OpenRule {
+                extensions: vec![key.to_lowercase()],
+                globs: vec![],
+                mimes: vec![],
+                command,
+            }),
+            _ => options.errors.push(format!("Skipped the {key} key of file_options.toml")),
+        }
+    }
+    options.rules.extend(legacy);
+    Ok(options)
 }
 
 pub fn read_file_options(config_path: Option<PathBuf>) -> FileOptions {
@@ -68,233 +130,21 @@ pub fn read_file_options(config_path: Option<PathBuf>) -> FileOptions {
     
     if config_path.exists() {
         if let Ok(contents) = fs::read_to_string(&config_path) {
-            let file_res: Result<FileOptions, Error> = toml::from_str(&contents);
-            if let Ok(options) = file_res {
-                return options;
+            match parse_file_options(&contents) {
+                Ok(options) => return options,
+                Err(err) => return FileOptions { rules: vec![], errors: vec![format!("Unable to read file_options.toml: {}", err.trim())] },
             }
         }
     }
-    FileOptions {
-        txt: None,
-        pdf: None,
-        mp4: None,
-        mp3: None,
-        c: None,
-        cpp: None,
-        rs: None,
-        png: None,
-        jpg: None,
-        svg: None,
-        sh: None,
-        toml: None,
-        html: None,
-        css: None,
-        js: None,
-        jsx: None,
-        ts: None,
-        tsx: None,
-        py: None,
-        md: None,
-        gitignore: None,
-        rb: None,
-        java: None,
-        kt: None,
-        json: None,
-        zig: None,
-        odin: None,
-        mkv: None,
-        avi: None,
-        odt: None,    
-        ods: None,
-        odp: None,
-        odg: None,
-        jpeg: None,
-        gif: None,
-    }
+    FileOptions::default()
 }
 
-pub fn handle_file_open(file: &Path, options: FileOptions) -> Result<(), String> {
-    if let Some(file_ext) = file.extension() {
-        match String::from(file_ext.to_string_lossy()).as_str() {
-            "txt" => {
-                if let Some(command) = options.txt {
-                    execute_command(command, file)?;
-                }
-            },
-            "pdf" => {
-                if let Some(command) = options.pdf {
-                    execute_command(command, file)?;
-                }
-            },
-            "mp4" => {
-                if let Some(command) = options.mp4 {
-                    execute_command(command, file)?;
-                }
-            },
-            "mp3" => {
-                if let Some(command) = options.mp3 {
-                    execute_command(command, file)?;
-                }
-            },
-            "c" => {
-                if let Some(command) = options.c {
-                    execute_command(command, file)?;
-                }
-            },
-            "cpp" => {
-                if let Some(command) = options.cpp {
-                    execute_command(command, file)?;
-                }
-            },
-            "rs" => {
-                if let Some(command) = options.rs {
-                    execute_command(command, file)?;
-                }
-            },
-            "png" => {
-                if let Some(command) = options.png {
-                    execute_command(command, file)?;
-                }
-            },
-            "jpg" => {
-                if let Some(command) = options.jpg {
-                    execute_command(command, file)?;
-                }
-            },
-            "svg" => {
-                if let Some(command) = options.svg {
-                    execute_command(command, file)?;
-                }
-            },
-            "sh" => {
-                if let Some(command) = options.sh {
-                    execute_command(command, file)?;
-                }
-            },
-            "html" => {
-                if let Some(command) = options.html {
-                    execute_command(command, file)?;
-                }
-            },
-            "css" => {
-                if let Some(command) = options.css {
-                    execute_command(command, file)?;
-                }
-            },
-            "js" => {
-                if let Some(command) = options.js {
-                    execute_command(command, file)?;
-                }
-            },
-            "jsx" => {
-                if let Some(command) = options.jsx {
-                    execute_command(command, file)?;
-                }
-            },
-            "ts" => {
-                if let Some(command) = options.ts {
-                    execute_command(command, file)?;
-                }
-            },
-            "tsx" => {
-                if let Some(command) = options.tsx {
-                    execute_command(command, file)?;
-                }
-            },
-            "md" => {
-                if let Some(command) = options.md {
-                    execute_command(command, file)?;
-                }
-            },
-            "py" => {
-                if let Some(command) = options.py {
-                    execute_command(command, file)?;
-                }
-            },
-            "java" => {
-                if let Some(command) = options.java {
-                    execute_command(command, file)?;
-                }
-            },
-            "kt" => {
-                if let Some(command) = options.kt {
-                    execute_command(command, file)?;
-                }
-            },
-            "odin" => {
-                if let Some(command) = options.odin {
-                    execute_command(command, file)?;
-                }
-            },
-            "zig" => {
-                if let Some(command) = options.zig {
-                    execute_command(command, file)?;
-                }
-            },
-            "gitignore" => {
-                if let Some(command) = options.gitignore {
-                    execute_command(command, file)?;
-                }
-            },
-            "rb" => {
-                if let Some(command) = options.rb {
-                    execute_command(command, file)?;
-                }
-            },
-            "json" => {
-                if let Some(command) = options.json {
-                    execute_command(command, file)?;
-                }
-            },
-            "toml" => {
-                if let Some(command) = options.toml {
-                    execute_command(command, file)?;
-                }
-            },
-            "mkv" => {
-                if let Some(command) = options.mkv {
-                    execute_command(command, file)?;
-                }
-            },
-            "avi" => {
-                if let Some(command) = options.avi {
-                    execute_command(command, file)?;
-                }
-            },
-            "odt" => {
-                if let Some(command) = options.odt {
-                    execute_command(command, file)?;
-                }
-            },
-            "ods" => {
-                if let Some(command) = options.ods {
-                    execute_command(command, file)?;
-                }
-            },
-            "odp" => {
-                if let Some(command) = options.odp {
-                    execute_command(command, file)?;
-                }
-            },
-            "odg" => {
-                if let Some(command) = options.odg {
-                    execute_command(command, file)?;
-                }
-            },
-            "jpeg" => {
-                if let Some(command) = options.jpeg {
-                    execute_command(command, file)?;
-                }
-            },
-            "gif" => {
-                if let Some(command) = options.gif {
-                    execute_command(command, file)?;
-                }
-            },
-            _ => {}
-        }
+/// Opens the file with the command of the first rule matching it. Nothing happens when none does.
+pub fn handle_file_open(file: &Path, options: &FileOptions) -> Result<(), String> {
+    if let Some(rule) = options.rules.iter().find(|rule| rule.matches(file)) {
+        execute_command(rule.command.clone(), file)?;
     }
-    Ok(()) 
+    Ok(())
 }
 
 fn split_command(cmd: String) -> (String, Vec<String>) {
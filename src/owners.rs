@@ -0,0 +1,163 @@
+use std::{
+    collections::HashMap,
+    fs::Metadata,
+    io,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+use crate::{App, jobs::{Attributes, JobItem, JobKind, JobOrigin}};
+
+/// uid -> user name, looked up once for all the Explorers
+static USER_NAMES: LazyLock<Mutex<HashMap<u32, String>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+/// gid -> group name, looked up once for all the Explorers
+static GROUP_NAMES: LazyLock<Mutex<HashMap<u32, String>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// (path, old (uid, gid), new (uid, gid))
+pub type OwnerChange = (PathBuf, (u32, u32), (u32, u32));
+
+/// (uid, gid) of the entry
+#[cfg(unix)]
+pub fn unix_owner(meta: &Metadata) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.uid(), meta.gid()))
+}
+
+#[cfg(not(unix))]
+pub fn unix_owner(_meta: &Metadata) -> Option<(u32, u32)> {
+    None
+}
+
+#[cfg(unix)]
+fn lookup_user(uid: u32) -> Option<String> {
+    uzers::get_user_by_uid(uid).map(|user| user.name().to_string_lossy().to_string())
+}
+
+#[cfg(not(unix))]
+fn lookup_user(_uid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+fn lookup_group(gid: u32) -> Option<String> {
+    uzers::get_group_by_gid(gid).map(|group| group.name().to_string_lossy().to_string())
+}
+
+#[cfg(not(unix))]
+fn lookup_group(_gid: u32) -> Option<String> {
+    None
+}
+
+/// Name of the user, or the uid if it has none
+pub fn user_name(uid: u32) -> String {
+    let mut names = USER_NAMES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    names.entry(uid).or_insert_with(|| lookup_user(uid).unwrap_or_else(|| uid.to_string())).clone()
+}
+
+/// Name of the group, or the gid if it has none
+pub fn group_name(gid: u32) -> String {
+    let mut names = GROUP_NAMES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    names.entry(gid).or_insert_with(|| lookup_group(gid).unwrap_or_else(|| gid.to_string())).clone()
+}
+
+// (uid, primary gid) of a user name or uid
+#[cfg(unix)]
+fn find_user(user: &str) -> Option<(u32, u32)> {
+    match user.parse::<u32>() {
+        Ok(uid) => Some((uid, uzers::get_user_by_uid(uid).map_or(uid, |user| user.primary_group_id()))),
+        Err(_) => uzers::get_user_by_name(user).map(|user| (user.uid(), user.primary_group_id())),
+    }
+}
+
+#[cfg(not(unix))]
+fn find_user(_user: &str) -> Option<(u32, u32)> {
+    None
+}
+
+// gid of a group name or gid
+#[cfg(unix)]
+fn find_group(group: &str) -> Option<u32> {
+    group.parse::<u32>().ok().or_else(|| uzers::get_group_by_name(group).map(|group| group.gid()))
+}
+
+#[cfg(not(unix))]
+fn find_group(_group: &str) -> Option<u32> {
+    None
+}
+
+/// Parsed `chown [-R] <USER>[:<GROUP>]` command
+pub struct ChownSpec {
+    uid: Option<u32>,
+    gid: Option<u32>,
+    recursive: bool,
+}
+
+/// Parses the arguments following `chown`. `USER:` takes the primary group of the user, `:GROUP` only changes the group.
+pub fn parse_chown_args(args: &str) -> Result<ChownSpec, String> {
+    const USAGE: &str = "Usage: chown [-R] <USER>[:<GROUP>]";
+    let mut recursive = false;
+    let mut owner: Option<&str> = None;
+    for arg in args.split_whitespace() {
+        match arg {
+            "-R" => recursive = true,
+            _ if owner.is_none() => owner = Some(arg),
+            _ => return Err(String::from(USAGE)),
+        }
+    }
+    let Some(owner) = owner else {
+        return Err(String::from(USAGE));
+    };
+    let (user, group) = match owner.split_once(':') {
+        Some((user, group)) => (user, Some(group)),
+        None => (owner, None),
+    };
+    let found_user = if user.is_empty() {
+        None
+    } else {
+        Some(find_user(user).ok_or_else(|| format!("Unknown user: {user}"))?)
+    };
+    let gid = match group {
+        None | Some("") if user.is_empty() => return Err(String::from(USAGE)),
+        None => None,
+        Some("") => found_user.map(|(_uid, primary_gid)| primary_gid),
+        Some(group) => Some(find_group(group).ok_or_else(|| format!("Unknown group: {group}"))?),
+    };
+    Ok(ChownSpec { uid: found_user.map(|(uid, _primary_gid)| uid), gid, recursive })
+}
+
+#[cfg(unix)]
+fn set_owner(path: &Path, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    std::os::unix::fs::lchown(path, uid, gid)
+}
+
+#[cfg(not(unix))]
+fn set_owner(_path: &Path, _uid: Option<u32>, _gid: Option<u32>) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "no Unix owners on this system"))
+}
+
+/// Sets the owner and group of the path, `None` keeping the one it has. A symlink is changed itself, not followed.
+/// Returns the change made, `None` if there was nothing to change.
+pub fn chown_one(path: &Path, uid: Option<u32>, gid: Option<u32>) -> Result<Option<OwnerChange>, String> {
+    let Some(old) = path.symlink_metadata().ok().as_ref().and_then(unix_owner) else {
+        return Err(format!("{}: unable to read the owner", path.to_string_lossy()));
+    };
+    let new = (uid.unwrap_or(old.0), gid.unwrap_or(old.1));
+    if new == old {
+        return Ok(None);
+    }
+    set_owner(path, uid, gid).map_err(|err| format!("{}: {err}", path.to_string_lossy()))?;
+    Ok(Some((path.to_path_buf(), old, new)))
+}
+
+/// Queues a job applying the `chown` command to the selected (or marked) items of the Explorer
+pub fn chown_command(app: &mut App, args: &str) -> Result<(), String> {
+    let spec = parse_chown_args(args)?;
+    let items: Vec<JobItem> = app
+        .explorer
+        .selected_paths()
+        .into_iter()
+        .map(|path| JobItem::with_attributes(path, Attributes::Owner(spec.uid, spec.gid), spec.recursive))
+        .collect();
+    app.jobs.enqueue(JobKind::Chown, items, JobOrigin::User);
+    Ok(())
+}
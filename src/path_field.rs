@@ -17,6 +17,8 @@ pub struct PathField {
     /// Current input mode
     pub input_mode: InputMode,
     pub in_focus: bool,
+    /// Where the input box was last drawn -> Used to focus it on a click
+    pub area: Rect,
     pub color_theme: ColorTheme,
 }
 
@@ -26,6 +28,7 @@ impl PathField {
             input: Input::new(String::from(path.to_string_lossy())),
             input_mode: InputMode::Normal,
             in_focus: false,
+            area: Rect::default(),
             color_theme: read_color_theme,
         }
     }
@@ -37,7 +40,8 @@ impl PathField {
         }
     }
 
-    pub fn render_input(&self, frame: &mut Frame, area: Rect) {
+    pub fn render_input(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
         // keep 2 for borders and 1 for cursor
         let width = area.width.max(3) - 3;
         let scroll = self.input.visual_scroll(width as usize);
@@ -0,0 +1,296 @@
+use std::{
+    fs::{self, Metadata},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{App, color_theme::ColorTheme, dependencies::HandlesInput, explorer::EntryType, jobs::{Attributes, JobItem, JobKind, JobOrigin}};
+
+/// setuid, setgid, sticky and the rwx bits of the owner, group and others
+const PERMISSION_BITS: u32 = 0o7777;
+const CLASSES: [&str; 3] = ["Owner", "Group", "Others"];
+/// Cursor position of the `Recursive` checkbox, after the 3 x 3 rwx grid
+const RECURSIVE_CHECKBOX: usize = 9;
+
+/// (path, old permissions, new permissions)
+pub type ModeChange = (PathBuf, u32, u32);
+
+/// `st_mode` of the entry -> Its type and permission bits
+#[cfg(unix)]
+pub fn unix_mode(meta: &Metadata) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    Some(meta.mode())
+}
+
+#[cfg(not(unix))]
+pub fn unix_mode(_meta: &Metadata) -> Option<u32> {
+    None
+}
+
+/// The mode as shown by `ls -l`, like `drwxr-xr-x`
+pub fn mode_string(mode: u32) -> String {
+    let mut text = String::with_capacity(10);
+    text.push(match mode & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o010000 => 'p',
+        0o140000 => 's',
+        0o060000 => 'b',
+        0o020000 => 'c',
+        _ => '-',
+    });
+    for (shift, special_bit, special) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+        let bits = (mode >> shift) & 0o7;
+        text.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        text.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        text.push(match (bits & 0o1 != 0, mode & special_bit != 0) {
+            (true, true) => special,
+            (false, true) => special.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    text
+}
+
+/// The umask of the process. It is read from `/proc`, as setting it to read it back would race with the jobs creating files.
+#[cfg(target_os = "linux")]
+pub fn current_umask() -> Option<u32> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let umask = status.lines().find_map(|line| line.strip_prefix("Umask:"))?;
+    u32::from_str_radix(umask.trim(), 8).ok()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn current_umask() -> Option<u32> {
+    // SAFETY: umask only swaps the mask of the process, the old one is set back right away
+    let umask = unsafe { libc::umask(0o022) };
+    unsafe { libc::umask(umask) };
+    Some(umask as u32)
+}
+
+#[cfg(not(unix))]
+pub fn current_umask() -> Option<u32> {
+    None
+}
+
+/// ` (-rw-r--r--, umask 0022)` -> Told after a file or directory is created, empty where there are no Unix permissions
+pub fn created_mode_note(path: &Path) -> String {
+    match (path.symlink_metadata().ok().as_ref().and_then(unix_mode), current_umask()) {
+        (Some(mode), Some(umask)) => format!(" ({}, umask {umask:04o})", mode_string(mode)),
+        (Some(mode), None) => format!(" ({})", mode_string(mode)),
+        _ => String::default(),
+    }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "no Unix permissions on this system"))
+}
+
+/// Sets the permission bits of the path. A symlink is skipped, as its own permissions can't be changed.
+/// Returns the change made, `None` if there was nothing to change.
+pub fn chmod_one(path: &Path, mode: u32) -> Result<Option<ModeChange>, String> {
+    let Ok(meta) = path.symlink_metadata() else {
+        return Ok(None);
+    };
+    let Some(old_mode) = unix_mode(&meta) else {
+        return Err(format!("{}: no Unix permissions", path.to_string_lossy()));
+    };
+    if meta.is_symlink() || old_mode & PERMISSION_BITS == mode {
+        return Ok(None);
+    }
+    set_mode(path, mode).map_err(|err| format!("{}: {err}", path.to_string_lossy()))?;
+    Ok(Some((path.to_path_buf(), old_mode & PERMISSION_BITS, mode)))
+}
+
+/// The chmod popup -> rwx checkboxes and an octal field for the permissions of an entry
+pub struct ChmodEditor {
+    pub path: PathBuf,
+    is_dir: bool,
+    /// Type bits of the mode, kept to show the mode like `ls -l`
+    file_type: u32,
+    /// Permission bits being edited
+    mode: u32,
+    /// Octal digits typed. Typing replaces the value shown, toggling a checkbox shows the new value.
+    octal: String,
+    typing: bool,
+    /// Checkbox under the cursor -> 0 to 8 through the grid row by row, then `RECURSIVE_CHECKBOX`
+    cursor: usize,
+    /// Everything under the directory gets the same permissions too
+    recursive: bool,
+    pub color_theme: ColorTheme,
+}
+
+impl ChmodEditor {
+    pub fn new(path: PathBuf, mode: u32, is_dir: bool, read_color_theme: ColorTheme) -> ChmodEditor {
+        ChmodEditor {
+            path,
+            is_dir,
+            file_type: mode & !PERMISSION_BITS,
+            mode: mode & PERMISSION_BITS,
+            octal: format!("{:04o}", mode & PERMISSION_BITS),
+            typing: false,
+            cursor: 0,
+            recursive: false,
+            color_theme: read_color_theme,
+        }
+    }
+
+    // Bit of the checkbox at `idx` in the grid
+    fn bit(idx: usize) -> u32 {
+        0o400 >> idx
+    }
+
+    fn toggle(&mut self) {
+        if self.cursor == RECURSIVE_CHECKBOX {
+            self.recursive = !self.recursive;
+            return;
+        }
+        self.mode ^= ChmodEditor::bit(self.cursor);
+        self.typing = false;
+        self.octal = format!("{:04o}", self.mode);
+    }
+
+    fn type_digit(&mut self, digit: char) {
+        if !self.typing {
+            self.octal.clear();
+            self.typing = true;
+        }
+        if self.octal.len() < 4 {
+            self.octal.push(digit);
+            self.mode = u32::from_str_radix(&self.octal, 8).unwrap_or(0);
+        }
+    }
+
+    fn erase_digit(&mut self) {
+        if self.typing {
+            self.octal.pop();
+            self.mode = u32::from_str_radix(&self.octal, 8).unwrap_or(0);
+        }
+    }
+
+    fn move_cursor(&mut self, rows: isize, cols: isize) {
+        let last_row: isize = if self.is_dir { 3 } else { 2 };
+        let (row, col) = if self.cursor == RECURSIVE_CHECKBOX { (3, 0) } else { ((self.cursor / 3) as isize, (self.cursor % 3) as isize) };
+        let row = (row + rows).clamp(0, last_row);
+        let col = (col + cols).clamp(0, 2);
+        self.cursor = if row == 3 { RECURSIVE_CHECKBOX } else { (row * 3 + col) as usize };
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let checkbox = |idx: usize, checked: bool| {
+            let style = if self.cursor == idx {
+                Style::default().add_modifier(Modifier::REVERSED).fg(self.color_theme.selector)
+            } else {
+                Style::default()
+            };
+            Span::styled(if checked { "[x]" } else { "[ ]" }, style)
+        };
+        let mut text = vec![Line::from("          Read   Write  Execute")];
+        for (row, class) in CLASSES.iter().enumerate() {
+            let mut spans = vec![Span::raw(format!(" {class:<9}"))];
+            for col in 0..3 {
+                let idx = row * 3 + col;
+                spans.push(checkbox(idx, self.mode & ChmodEditor::bit(idx) != 0));
+                spans.push(Span::raw("    "));
+            }
+            text.push(Line::from(spans));
+        }
+        text.push(Line::from(""));
+        text.push(Line::from(vec![
+            Span::raw(" Octal    "),
+            Span::styled(format!("{:<4}", self.octal), Style::default().add_modifier(Modifier::UNDERLINED)),
+            Span::raw(format!("   {}", mode_string(self.file_type | self.mode))),
+        ]));
+        if self.is_dir {
+            text.push(Line::from(vec![
+                Span::raw(" "),
+                checkbox(RECURSIVE_CHECKBOX, self.recursive),
+                Span::raw(" Recursive (everything under it too)"),
+            ]));
+        }
+        let name = self.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let instructions = Line::from(vec![
+            " <Space>".blue().bold(),
+            " Toggle ".into(),
+            "<0-7>".blue().bold(),
+            " Octal ".into(),
+            "<Enter>".blue().bold(),
+            " Apply ".into(),
+            "<Esc>".blue().bold(),
+            " Cancel ".into(),
+        ]);
+        let popup = Paragraph::new(text)
+            .style(Style::default().fg(self.color_theme.primary))
+            .bg(Color::Black)
+            .block(
+                Block::bordered()
+                    .border_type(ratatui::widgets::BorderType::Rounded)
+                    .title(format!(" Permissions: {name} "))
+                    .title_bottom(instructions)
+                    .border_style(Style::new().fg(self.color_theme.border)),
+            );
+        frame.render_widget(popup, area);
+    }
+}
+
+impl HandlesInput for ChmodEditor {
+    fn handle_input(&mut self, event: Event) -> Result<(), String> {
+        if let Event::Key(key_event) = event && key_event.kind == KeyEventKind::Press {
+            match key_event.code {
+                KeyCode::Char(' ') | KeyCode::Char('x') => self.toggle(),
+                KeyCode::Char(digit @ '0'..='7') => self.type_digit(digit),
+                KeyCode::Backspace => self.erase_digit(),
+                KeyCode::Char('j') | KeyCode::Down => self.move_cursor(1, 0),
+                KeyCode::Char('k') | KeyCode::Up => self.move_cursor(-1, 0),
+                KeyCode::Char('l') | KeyCode::Right => self.move_cursor(0, 1),
+                KeyCode::Char('h') | KeyCode::Left => self.move_cursor(0, -1),
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Opens the chmod popup for the selected entry of the Explorer
+pub fn open_chmod_editor(app: &mut App) {
+    let Some(entry) = app.explorer.state.selected().and_then(|idx| app.explorer.files.get(idx)) else {
+        return;
+    };
+    if entry.e_type == EntryType::Link {
+        app.log_panel.set_log(String::from("A symlink has no permissions of its own, press g to go to its target"));
+        return;
+    }
+    let Some(mode) = entry.mode else {
+        app.log_panel.set_log(String::from("Permissions can't be changed on this system"));
+        return;
+    };
+    let path = app.explorer.root_path.join(&entry.name);
+    app.chmod_editor = Some(ChmodEditor::new(path, mode, entry.e_type == EntryType::Dir, app.explorer.color_theme.clone()));
+}
+
+/// Queues a job applying the permissions set in the chmod popup and closes it
+pub fn apply_chmod(app: &mut App) {
+    let Some(editor) = app.chmod_editor.take() else {
+        return;
+    };
+    let item = JobItem::with_attributes(editor.path, Attributes::Mode(editor.mode), editor.is_dir && editor.recursive);
+    app.jobs.enqueue(JobKind::Chmod, vec![item], JobOrigin::User);
+}
@@ -0,0 +1,219 @@
+use std::{
+    fs::{self, File, Metadata},
+    io::Read,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+};
+use syntect::{easy::HighlightLines, highlighting::{Theme, ThemeSet}, parsing::SyntaxSet, util::LinesWithEndings};
+
+use crate::{AppEvent, color_theme::ColorTheme, dependencies::is_binary, explorer::EntryType, file_deps::{device_numbers, entry_type, get_data}, sort::{SortOptions, sort_entries}};
+
+/// Only the beginning of a file is read for the preview
+const MAX_PREVIEW_BYTES: u64 = 64 * 1024;
+const MAX_PREVIEW_LINES: usize = 200;
+/// Bytes shown in the hex dump of binary files
+const MAX_HEX_BYTES: usize = 1024;
+
+struct PreviewRequest {
+    id: u64,
+    path: PathBuf,
+    include_hidden: bool,
+    sort: SortOptions,
+}
+
+pub struct Preview {
+    pub shown: bool,
+    pub title: String,
+    pub lines: Vec<Line<'static>>,
+    /// Path the last request was made for
+    requested: Option<PathBuf>,
+    request_id: u64,
+    request_tx: Sender<PreviewRequest>,
+    pub color_theme: ColorTheme,
+}
+
+// Syntax definitions and themes are loaded once, on the first text preview
+struct Highlighter {
+    syntax_set: SyntaxSet,
+    theme: Theme,
+}
+
+impl Highlighter {
+    fn new() -> Highlighter {
+        let mut theme_set = ThemeSet::load_defaults();
+        Highlighter {
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            theme: theme_set.themes.remove("base16-ocean.dark").unwrap_or_default(),
+        }
+    }
+
+    fn highlight(&self, path: &Path, text: &str) -> Vec<Line<'static>> {
+        let syntax = path
+            .extension()
+            .and_then(|ext| self.syntax_set.find_syntax_by_extension(&ext.to_string_lossy()))
+            .or_else(|| self.syntax_set.find_syntax_by_first_line(text))
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+        let mut highlighter = HighlightLines::new(syntax, &self.theme);
+        LinesWithEndings::from(text)
+            .take(MAX_PREVIEW_LINES)
+            .map(|line| match highlighter.highlight_line(line, &self.syntax_set) {
+                Ok(ranges) => Line::from(
+                    ranges
+                        .into_iter()
+                        .map(|(style, piece)| {
+                            Span::styled(
+                                piece.trim_end_matches(['\n', '\r']).replace('\t', "    "),
+                                Style::default().fg(Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b)),
+                            )
+                        })
+                        .collect::<Vec<Span>>(),
+                ),
+                Err(_) => Line::from(line.trim_end_matches(['\n', '\r']).replace('\t', "    ")),
+            })
+            .collect()
+    }
+}
+
+fn hex_dump(bytes: &[u8]) -> Vec<Line<'static>> {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{byte:02x}")).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+                .collect();
+            Line::from(vec![
+                Span::styled(format!("{:08x}  ", row * 16), Style::default().fg(Color::DarkGray)),
+                Span::raw(format!("{:<48} ", hex.join(" "))),
+                Span::styled(ascii, Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect()
+}
+
+fn preview_directory(request: &PreviewRequest) -> Vec<Line<'static>> {
+    match get_data(&request.path, request.include_hidden, false, false, false) {
+        Ok(mut entries) => {
+            sort_entries(&mut entries, &request.sort);
+            entries
+                .iter()
+                .map(|entry| Line::from(format!("{} {}", entry.glyph(), entry.display_name())))
+                .collect()
+        },
+        Err(err) => vec![Line::from(err)],
+    }
+}
+
+fn preview_file(request: &PreviewRequest, highlighter: &mut Option<Highlighter>) -> Vec<Line<'static>> {
+    let mut head: Vec<u8> = vec![];
+    let read_result = File::open(&request.path).and_then(|file| file.take(MAX_PREVIEW_BYTES).read_to_end(&mut head));
+    if let Err(err) = read_result {
+        return vec![Line::from(format!("Unable to read the file: {err}"))];
+    }
+    if head.is_empty() {
+        return vec![Line::from("(empty file)")];
+    }
+    if is_binary(&head) {
+        return hex_dump(&head[..head.len().min(MAX_HEX_BYTES)]);
+    }
+    let text = String::from_utf8_lossy(&head);
+    highlighter.get_or_insert_with(Highlighter::new).highlight(&request.path, &text)
+}
+
+// Told in place of the contents of a FIFO, a socket or a device
+fn preview_special(meta: &Metadata) -> Vec<Line<'static>> {
+    let e_type = entry_type(meta.file_type());
+    let mut lines = vec![Line::from(format!("A {}, not read for the preview", e_type.label()))];
+    if matches!(e_type, EntryType::BlockDevice | EntryType::CharDevice) {
+        let (major, minor) = device_numbers(meta);
+        lines.push(Line::from(format!("Device number: {major}, {minor}")));
+    }
+    lines
+}
+
+// Runs on the preview thread. Only the latest request is served when several are waiting.
+fn preview_worker(request_rx: mpsc::Receiver<PreviewRequest>, event_tx: Sender<AppEvent>) {
+    let mut highlighter: Option<Highlighter> = None;
+    while let Ok(mut request) = request_rx.recv() {
+        while let Ok(newer) = request_rx.try_recv() {
+            request = newer;
+        }
+        // Only regular files are opened. Reading a FIFO or a device could block forever.
+        let lines = match fs::metadata(&request.path) {
+            Ok(meta) if meta.is_dir() => preview_directory(&request),
+            Ok(meta) if meta.is_file() => preview_file(&request, &mut highlighter),
+            Ok(meta) => preview_special(&meta),
+            Err(err) => vec![Line::from(format!("Unable to read the entry: {err}"))],
+        };
+        if event_tx.send(AppEvent::Preview(request.id, lines)).is_err() {
+            break;
+        }
+    }
+}
+
+impl Preview {
+    pub fn new(event_tx: Sender<AppEvent>, read_color_theme: ColorTheme) -> Preview {
+        let (request_tx, request_rx) = mpsc::channel::<PreviewRequest>();
+        thread::spawn(move || preview_worker(request_rx, event_tx));
+        Preview {
+            shown: false,
+            title: String::default(),
+            lines: vec![],
+            requested: None,
+            request_id: 0,
+            request_tx,
+            color_theme: read_color_theme,
+        }
+    }
+
+    /// Asks the preview thread for the preview of `path`, unless it was already asked for
+    pub fn request(&mut self, path: Option<PathBuf>, include_hidden: bool, sort: SortOptions) {
+        if !self.shown || path == self.requested {
+            return;
+        }
+        self.requested = path.clone();
+        self.request_id += 1;
+        self.lines.clear();
+        match path {
+            Some(path) => {
+                self.title = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                let _ = self.request_tx.send(PreviewRequest { id: self.request_id, path, include_hidden, sort });
+            },
+            None => self.title.clear(),
+        }
+    }
+
+    pub fn set_content(&mut self, id: u64, lines: Vec<Line<'static>>) {
+        if id == self.request_id {
+            self.lines = lines;
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.shown = !self.shown;
+        self.requested = None;
+        self.lines.clear();
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let preview = Paragraph::new(self.lines.clone())
+            .style(Style::default().fg(self.color_theme.primary))
+            .block(
+                Block::bordered()
+                    .border_type(ratatui::widgets::BorderType::Rounded)
+                    .title(format!(" Preview: {} ", self.title)),
+            );
+        frame.render_widget(preview, area);
+    }
+}
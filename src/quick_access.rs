@@ -1,9 +1,9 @@
 use std::{fs, path::{Path, PathBuf}};
 use std::env;
-use crossterm::event::{Event, KeyCode, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
 use ratatui::{
     Frame,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Position, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::Text,
     widgets::{
@@ -13,7 +13,7 @@ use ratatui::{
 use serde::{Deserialize, Serialize};
 use toml::de::Error;
 
-use crate::{App, color_theme::ColorTheme, dependencies::HandlesInput};
+use crate::{App, color_theme::ColorTheme, dependencies::{HandlesInput, table_row_at}};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QAFileEntry {
@@ -32,6 +32,8 @@ pub struct QuickAccess {
     pub state: TableState,
     pub scroll_state: ScrollbarState,
     pub in_focus: bool,
+    /// Where the table was last drawn -> Used to find the row clicked with the mouse
+    pub area: Rect,
     pub color_theme: ColorTheme,
 }
 
@@ -53,6 +55,7 @@ impl QuickAccess {
             state: TableState::default().with_selected(0),
             scroll_state: ScrollbarState::new((&data_vec.len() - 1) * ITEM_HEIGHT),
             in_focus: false,
+            area: Rect::default(),
             color_theme: read_color_theme,
         }
     }
@@ -88,6 +91,7 @@ impl QuickAccess {
     }
 
     pub fn create_qa_entries_table(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
         let header_style = Style::default().fg(Color::Black).bg(self.color_theme.header);
         let selected_row_style = Style::default()
             .add_modifier(Modifier::REVERSED)
@@ -242,6 +246,21 @@ impl HandlesInput for QuickAccess {
                     }
                 }
             }
+            Event::Mouse(mouse_event) => {
+                let position = Position::new(mouse_event.column, mouse_event.row);
+                let selected = self.state.selected().unwrap_or(0);
+                match mouse_event.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if let Some(idx) = table_row_at(self.area, self.state.offset(), position) && idx < self.entries.len() {
+                            self.state.select(Some(idx));
+                            self.scroll_state = self.scroll_state.position(idx * ITEM_HEIGHT);
+                        }
+                    },
+                    MouseEventKind::ScrollDown if selected + 1 < self.entries.len() => self.next_row(),
+                    MouseEventKind::ScrollUp if selected > 0 => self.previous_row(),
+                    _ => (),
+                }
+            },
             _ => {}
         }
         Ok(())
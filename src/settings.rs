@@ -0,0 +1,99 @@
+use std::{fs, path::{Path, PathBuf}};
+use serde::Deserialize;
+use toml::de::Error;
+
+use crate::{columns::{Column, default_columns}, conflict::ConflictPolicy, dir_sizes::DirSizeMode, sort::{SortKey, SortOptions}};
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub sort_by: SortKey,
+    pub sort_descending: bool,
+    pub directories_first: bool,
+    /// What to do when a paste, move or rename runs into an existing name
+    pub conflict_policy: ConflictPolicy,
+    /// What the Size column shows for directories
+    pub directory_sizes: DirSizeMode,
+    /// Columns of the Explorer, in their order
+    pub columns: Vec<Column>,
+    /// Nerd Font icons in the Type column. Needs a Nerd Font in the terminal.
+    pub nerd_font_icons: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            sort_by: SortKey::Name,
+            sort_descending: false,
+            directories_first: true,
+            conflict_policy: ConflictPolicy::Ask,
+            directory_sizes: DirSizeMode::Off,
+            columns: default_columns(),
+            nerd_font_icons: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn sort_options(&self) -> SortOptions {
+        SortOptions {
+            key: self.sort_by,
+            descending: self.sort_descending,
+            dirs_first: self.directories_first,
+        }
+    }
+}
+
+pub fn settings_path(config_path: Option<PathBuf>) -> PathBuf {
+    match config_path {
+        Some(input_config_path) => input_config_path,
+        None => {
+            #[cfg(target_os = "windows")]
+            {
+                PathBuf::from("D:\\Applications\\columbus\\settings.toml")
+            }
+            #[cfg(not(target_os = "windows"))]
+            {
+                use std::env;
+
+                let mut file_config_path: PathBuf;
+                if let Ok(home_path) = env::var("XDG_CONFIG_HOME") {
+                    file_config_path = PathBuf::from(home_path);
+                } else {
+                    file_config_path = PathBuf::from(".");
+                }
+                file_config_path.push("columbus/settings.toml");
+                file_config_path
+            }
+        }
+    }
+}
+
+pub fn read_settings(config_path: Option<PathBuf>) -> Settings {
+    let config_path = settings_path(config_path);
+    if config_path.exists() && let Ok(contents) = fs::read_to_string(&config_path) {
+        let file_res: Result<Settings, Error> = toml::from_str(&contents);
+        if let Ok(settings) = file_res {
+            return settings;
+        }
+    }
+    Settings::default()
+}
+
+/// Writes the columns to the settings file, keeping the other settings in it
+pub fn save_columns(config_path: &Path, columns: &[Column]) -> Result<(), String> {
+    let mut table = match fs::read_to_string(config_path) {
+        Ok(contents) => contents
+            .parse::<toml::Table>()
+            .map_err(|err| format!("The columns weren't saved, {} can't be read: {err}", config_path.to_string_lossy()))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => toml::Table::new(),
+        Err(err) => return Err(format!("The columns weren't saved, {} can't be read: {err}", config_path.to_string_lossy())),
+    };
+    let columns = toml::Value::try_from(columns).map_err(|err| format!("Unable to save the columns: {err}"))?;
+    table.insert(String::from("columns"), columns);
+    let contents = toml::to_string(&table).map_err(|err| format!("Unable to save the columns: {err}"))?;
+    if let Some(dir) = config_path.parent() {
+        fs::create_dir_all(dir).map_err(|err| format!("Unable to save the columns: {err}"))?;
+    }
+    fs::write(config_path, contents).map_err(|err| format!("Unable to save the columns: {err}"))
+}
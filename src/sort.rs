@@ -0,0 +1,146 @@
+use std::{cmp::Ordering, path::Path};
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::explorer::FileEntry;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    #[default]
+    Name,
+    Extension,
+    Size,
+    Modified,
+}
+
+impl SortKey {
+    /// To cycle through the sort keys -> Used to handle the `s` Keyevent in Explorer
+    pub fn next(&self) -> Self {
+        let variants: Vec<SortKey> = SortKey::iter().collect();
+        let current_index = variants.iter().position(|&v| v == *self).unwrap_or(0);
+        variants[(current_index + 1) % variants.len()]
+    }
+
+    pub fn from_arg(arg: &str) -> Option<SortKey> {
+        match arg {
+            "name" => Some(SortKey::Name),
+            "ext" | "extension" => Some(SortKey::Extension),
+            "size" => Some(SortKey::Size),
+            "modified" | "mtime" | "time" => Some(SortKey::Modified),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Extension => "extension",
+            SortKey::Size => "size",
+            SortKey::Modified => "modified time",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOptions {
+    pub key: SortKey,
+    pub descending: bool,
+    pub dirs_first: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        SortOptions {
+            key: SortKey::Name,
+            descending: false,
+            dirs_first: true,
+        }
+    }
+}
+
+impl SortOptions {
+    /// Arrow shown next to the header of the sorted column
+    pub fn arrow(&self) -> &'static str {
+        if self.descending { "▼" } else { "▲" }
+    }
+}
+
+fn extension_of(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+// Splits off the leading run of digits (or non digits) from the given characters
+fn take_chunk(chars: &mut std::iter::Peekable<std::str::Chars>) -> (bool, String) {
+    let mut chunk = String::new();
+    let is_digit = chars.peek().is_some_and(|c| c.is_ascii_digit());
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() != is_digit {
+            break;
+        }
+        chunk.push(c);
+        chars.next();
+    }
+    (is_digit, chunk)
+}
+
+/// Case insensitive natural ordering -> `file2` comes before `file10`
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let a_lower = a.to_lowercase();
+    let b_lower = b.to_lowercase();
+    let mut a_chars = a_lower.chars().peekable();
+    let mut b_chars = b_lower.chars().peekable();
+    loop {
+        match (a_chars.peek().is_some(), b_chars.peek().is_some()) {
+            (false, false) => break,
+            (false, true) => return Ordering::Less,
+            (true, false) => return Ordering::Greater,
+            (true, true) => {}
+        }
+        let (a_is_digit, a_chunk) = take_chunk(&mut a_chars);
+        let (b_is_digit, b_chunk) = take_chunk(&mut b_chars);
+        let ordering = if a_is_digit && b_is_digit {
+            let a_num = a_chunk.trim_start_matches('0');
+            let b_num = b_chunk.trim_start_matches('0');
+            a_num
+                .len()
+                .cmp(&b_num.len())
+                .then_with(|| a_num.cmp(b_num))
+                .then_with(|| a_chunk.len().cmp(&b_chunk.len()))
+        } else {
+            a_chunk.cmp(&b_chunk)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    // Names differing only in case are kept in a stable order
+    a.cmp(b)
+}
+
+pub fn compare_entries(a: &FileEntry, b: &FileEntry, options: &SortOptions) -> Ordering {
+    if options.dirs_first {
+        let a_dir = a.leads_to_dir();
+        let b_dir = b.leads_to_dir();
+        if a_dir != b_dir {
+            return b_dir.cmp(&a_dir);
+        }
+    }
+    let ordering = match options.key {
+        SortKey::Name => natural_cmp(&a.name, &b.name),
+        SortKey::Extension => extension_of(&a.name)
+            .cmp(&extension_of(&b.name))
+            .then_with(|| natural_cmp(&a.name, &b.name)),
+        SortKey::Size => a.size_bytes.cmp(&b.size_bytes).then_with(|| natural_cmp(&a.name, &b.name)),
+        SortKey::Modified => a.modified.cmp(&b.modified).then_with(|| natural_cmp(&a.name, &b.name)),
+    };
+    if options.descending { ordering.reverse() } else { ordering }
+}
+
+pub fn sort_entries(entries: &mut [FileEntry], options: &SortOptions) {
+    entries.sort_by(|a, b| compare_entries(a, b, options));
+}
@@ -0,0 +1,91 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::Tabs,
+};
+
+use crate::{App, CurrentWidget, dual_pane::active_pane_name, explorer::Explorer, history::History};
+
+/// State of an Explorer tab -> The directory listing with its cursor, marks and filter, and its history
+pub struct Tab {
+    pub explorer: Explorer,
+    pub history: History,
+}
+
+// Swaps the live state of the app with the one stored in the slot `idx`
+fn swap_with_slot(app: &mut App, idx: usize) {
+    std::mem::swap(&mut app.explorer, &mut app.tabs[idx].explorer);
+    std::mem::swap(&mut app.history, &mut app.tabs[idx].history);
+}
+
+/// Makes the tab at `target` the active one.
+/// The live state of the active tab is in `app.explorer` and `app.history`, its own slot holds an outdated copy.
+pub fn switch_tab(app: &mut App, target: usize) {
+    if target >= app.tabs.len() || target == app.active_tab {
+        return;
+    }
+    // Parking the active tab in its slot, then bringing the target out of its slot
+    swap_with_slot(app, app.active_tab);
+    swap_with_slot(app, target);
+    app.active_tab = target;
+    activate(app);
+}
+
+// Brings the rest of the UI in line with the tab that was just made active
+fn activate(app: &mut App) {
+    app.explorer.in_focus = app.focus_on == CurrentWidget::Explorer;
+    app.explorer.delete_ongoing = false;
+    app.explorer.pane_name = active_pane_name(app);
+    app.explorer.reload();
+    app.path_field.set_value(String::from(app.explorer.root_path.to_string_lossy()));
+}
+
+/// Opens a new tab next to the active one on the same directory
+pub fn new_tab(app: &mut App) {
+    let mut explorer = app.explorer.clone();
+    explorer.marked.clear();
+    explorer.filter.clear();
+    let history = History::new(&explorer.root_path, app.history.color_theme.clone());
+    app.tabs.insert(app.active_tab + 1, Tab { explorer, history });
+    switch_tab(app, app.active_tab + 1);
+}
+
+/// Closes the active tab. The last tab can't be closed.
+pub fn close_tab(app: &mut App) {
+    if app.tabs.len() <= 1 {
+        app.log_panel.set_log(String::from("The last tab can't be closed"));
+        return;
+    }
+    app.tabs.remove(app.active_tab);
+    let target = app.active_tab.min(app.tabs.len() - 1);
+    // The live state of the closed tab is dropped in exchange of the target
+    swap_with_slot(app, target);
+    app.active_tab = target;
+    activate(app);
+}
+
+pub fn next_tab(app: &mut App) {
+    switch_tab(app, (app.active_tab + 1) % app.tabs.len());
+}
+
+pub fn previous_tab(app: &mut App) {
+    switch_tab(app, if app.active_tab == 0 { app.tabs.len() - 1 } else { app.active_tab - 1 });
+}
+
+pub fn render_tab_bar(app: &App, frame: &mut Frame, area: Rect) {
+    let titles = (0..app.tabs.len()).map(|idx| {
+        let root_path = if idx == app.active_tab { &app.explorer.root_path } else { &app.tabs[idx].explorer.root_path };
+        let name = root_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| root_path.to_string_lossy().to_string());
+        format!(" {}: {} ", idx + 1, name)
+    });
+    let tab_bar = Tabs::new(titles)
+        .select(app.active_tab)
+        .style(Style::default().fg(app.explorer.color_theme.primary))
+        .highlight_style(Style::default().fg(app.explorer.color_theme.selector).add_modifier(Modifier::REVERSED))
+        .divider("|");
+    frame.render_widget(tab_bar, area);
+}
@@ -0,0 +1,320 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use crate::{App, dependencies::rename_all, jobs::{Attributes, Job, JobItem, JobKind, JobOrigin}, links::create_link, owners::OwnerChange, permissions::ModeChange};
+
+/// Operations kept to be undone. The oldest are dropped past this.
+const MAX_UNDO: usize = 100;
+
+/// A file operation, recorded so that it can be undone and redone
+#[derive(Debug, Clone)]
+pub enum Operation {
+    Create { path: PathBuf, is_dir: bool },
+    /// (old path, new path) pairs
+    Rename(Vec<(PathBuf, PathBuf)>),
+    /// (source, destination) pairs
+    Move(Vec<(PathBuf, PathBuf)>),
+    /// (source, copy) pairs
+    Copy(Vec<(PathBuf, PathBuf)>),
+    Trash(Vec<PathBuf>),
+    Chmod(Vec<ModeChange>),
+    Chown(Vec<OwnerChange>),
+    /// A link made at `path`. `target` is stored in it for a symbolic link.
+    Link { path: PathBuf, target: PathBuf, symbolic: bool },
+    /// An operation that can't be undone, like a permanent delete. Kept to tell about it when undo reaches it.
+    Irreversible(String),
+}
+
+impl Operation {
+    fn describe(&self) -> String {
+        match self {
+            Operation::Create { path, is_dir } => format!(
+                "creating the {} {}",
+                if *is_dir { "directory" } else { "file" },
+                path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+            ),
+            Operation::Rename(pairs) => format!("renaming {} item(s)", pairs.len()),
+            Operation::Move(pairs) => format!("moving {} item(s)", pairs.len()),
+            Operation::Copy(pairs) => format!("copying {} item(s)", pairs.len()),
+            Operation::Trash(paths) => format!("trashing {} item(s)", paths.len()),
+            Operation::Chmod(changes) => format!("changing the permissions of {} item(s)", changes.len()),
+            Operation::Chown(changes) => format!("changing the owner of {} item(s)", changes.len()),
+            Operation::Link { path, .. } => format!(
+                "creating the link {}",
+                path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+            ),
+            Operation::Irreversible(description) => description.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UndoHistory {
+    undo: Vec<Operation>,
+    redo: Vec<Operation>,
+    /// Bumped whenever the redo stack is cleared -> What an undo job started before can't be redone anymore
+    generation: u64,
+}
+
+fn exists(path: &Path) -> bool {
+    path.symlink_metadata().is_ok()
+}
+
+fn reversed(pairs: &[(PathBuf, PathBuf)]) -> Vec<(PathBuf, PathBuf)> {
+    pairs.iter().rev().map(|(src, dest)| (dest.clone(), src.clone())).collect()
+}
+
+// Renames the pairs whose source is there and whose destination is free or being vacated by another pair.
+// Returns the ones that were renamed.
+fn rename_pairs(pairs: &[(PathBuf, PathBuf)]) -> Vec<(PathBuf, PathBuf)> {
+    let possible: Vec<(PathBuf, PathBuf)> = pairs
+        .iter()
+        .filter(|(src, dest)| exists(src) && (!exists(dest) || pairs.iter().any(|(other_src, _)| other_src == dest)))
+        .cloned()
+        .collect();
+    rename_all(&possible).0
+}
+
+#[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))))]
+fn restore_from_trash(paths: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let trashed = trash::os_limited::list().map_err(|err| format!("Unable to read the trash: {err}"))?;
+    // The latest item trashed from each path
+    let to_restore: Vec<trash::TrashItem> = paths
+        .iter()
+        .filter_map(|path| {
+            trashed
+                .iter()
+                .filter(|item| item.original_path() == *path)
+                .max_by_key(|item| item.time_deleted)
+                .cloned()
+        })
+        .collect();
+    let restored = to_restore.iter().map(|item| item.original_path()).collect();
+    trash::os_limited::restore_all(to_restore).map_err(|err| format!("Unable to restore from the trash: {err}"))?;
+    Ok(restored)
+}
+
+#[cfg(not(any(target_os = "windows", all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android")))))]
+fn restore_from_trash(_paths: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    Err(String::from("Restoring from the trash is not supported on this system"))
+}
+
+// The pairs whose source is there and whose destination is free. Nothing is ever overwritten by undo or redo.
+fn job_items(pairs: Vec<(PathBuf, PathBuf)>) -> Vec<JobItem> {
+    pairs.into_iter().filter(|(src, dest)| exists(src) && !exists(dest)).map(|(src, dest)| JobItem::new(src, dest)).collect()
+}
+
+// One item per path, setting the mode paired with it
+fn chmod_items(modes: impl Iterator<Item = (PathBuf, u32)>) -> Vec<JobItem> {
+    modes.map(|(path, mode)| JobItem::with_attributes(path, Attributes::Mode(mode), false)).collect()
+}
+
+// One item per path, setting the (uid, gid) paired with it
+fn chown_items(owners: impl Iterator<Item = (PathBuf, (u32, u32))>) -> Vec<JobItem> {
+    owners.map(|(path, (uid, gid))| JobItem::with_attributes(path, Attributes::Owner(Some(uid), Some(gid)), false)).collect()
+}
+
+fn move_to_trash(paths: Vec<PathBuf>) -> Result<Vec<PathBuf>, String> {
+    let paths: Vec<PathBuf> = paths.into_iter().filter(|path| exists(path)).collect();
+    if !paths.is_empty() {
+        trash::delete_all(&paths).map_err(|err| format!("Error in moving item to trash: {err}"))?;
+    }
+    Ok(paths)
+}
+
+impl UndoHistory {
+    /// Records an operation done by the user. Anything that could be redone is forgotten.
+    pub fn record(&mut self, operation: Operation) {
+        self.undo.push(operation);
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        self.generation += 1;
+    }
+
+    /// Records what a finished job did, on the stack its origin calls for
+    pub fn record_job(&mut self, job: &Job) {
+        // Something new was done while the undo job ran, redoing it would go back past that
+        if let JobOrigin::Undo(generation) = job.origin && generation != self.generation {
+            return;
+        }
+        let done: Vec<(PathBuf, PathBuf)> = match job.kind {
+            JobKind::Copy => job.items.iter().filter(|item| exists(&item.dest)).map(|item| (item.src.clone(), item.dest.clone())).collect(),
+            JobKind::Move => job.items.iter().filter(|item| exists(&item.dest) && !exists(&item.src)).map(|item| (item.src.clone(), item.dest.clone())).collect(),
+            JobKind::Delete => {
+                self.record(Operation::Irreversible(format!("The permanent delete of {} item(s)", job.items.len())));
+                return;
+            },
+            JobKind::Chmod | JobKind::Chown => {
+                self.record_attributes_job(job);
+                return;
+            },
+        };
+        // The items overwritten were deleted for good, undoing the rest would leave them lost all the same
+        if !job.overwritten.is_empty() {
+            self.record(Operation::Irreversible(format!(
+                "The {} of {} item(s) overwriting {} existing item(s)",
+                job.kind.label().to_lowercase(),
+                job.items.len(),
+                job.overwritten.len()
+            )));
+            return;
+        }
+        if done.is_empty() {
+            return;
+        }
+        let operation = if job.kind == JobKind::Copy { Operation::Copy(done) } else { Operation::Move(done) };
+        match job.origin {
+            JobOrigin::User => self.record(operation),
+            JobOrigin::Redo => self.undo.push(operation),
+            // The job moved the items back, the pairs are turned around to the original direction
+            JobOrigin::Undo(_) => match operation {
+                Operation::Move(pairs) => self.redo.push(Operation::Move(reversed(&pairs))),
+                operation => self.redo.push(operation),
+            },
+        }
+    }
+
+    fn record_attributes_job(&mut self, job: &Job) {
+        if job.mode_changes.is_empty() && job.owner_changes.is_empty() {
+            return;
+        }
+        let operation = match (job.kind, job.origin) {
+            // The job set the old attributes back, the changes are turned around to the original direction
+            (JobKind::Chmod, JobOrigin::Undo(_)) => {
+                Operation::Chmod(job.mode_changes.iter().map(|(path, new_mode, old_mode)| (path.clone(), *old_mode, *new_mode)).collect())
+            },
+            (JobKind::Chmod, _) => Operation::Chmod(job.mode_changes.clone()),
+            (_, JobOrigin::Undo(_)) => Operation::Chown(job.owner_changes.iter().map(|(path, new, old)| (path.clone(), *old, *new)).collect()),
+            _ => Operation::Chown(job.owner_changes.clone()),
+        };
+        match job.origin {
+            JobOrigin::User => self.record(operation),
+            JobOrigin::Redo => self.undo.push(operation),
+            JobOrigin::Undo(_) => self.redo.push(operation),
+        }
+    }
+}
+
+/// Reverses the last operation. Moves back and permission or owner changes are run as a job and become redoable once it finishes.
+pub fn undo(app: &mut App) {
+    let Some(operation) = app.undo.undo.pop() else {
+        app.log_panel.set_log(String::from("Nothing to undo"));
+        return;
+    };
+    let description = operation.describe();
+    // Kept to be put back when it fails. An irreversible one always fails -> Undo stops there.
+    let result: Result<Option<Operation>, String> = match operation.clone() {
+        Operation::Irreversible(_) => Err(format!("{description} can't be undone, nor anything done before it")),
+        Operation::Create { path, is_dir } => move_to_trash(vec![path.clone()]).map(|_| Some(Operation::Create { path, is_dir })),
+        Operation::Rename(pairs) => Ok(Some(Operation::Rename(reversed(&rename_pairs(&reversed(&pairs)))))),
+        Operation::Move(pairs) => {
+            let items = job_items(reversed(&pairs));
+            app.jobs.enqueue(JobKind::Move, items, JobOrigin::Undo(app.undo.generation));
+            Ok(None)
+        },
+        Operation::Copy(pairs) => {
+            let copies = pairs.iter().map(|(_src, dest)| dest.clone()).collect();
+            move_to_trash(copies).map(|trashed| {
+                Some(Operation::Copy(pairs.into_iter().filter(|(_src, dest)| trashed.contains(dest)).collect()))
+            })
+        },
+        Operation::Trash(paths) => restore_from_trash(&paths).map(|restored| Some(Operation::Trash(restored))),
+        Operation::Chmod(changes) => {
+            let items = chmod_items(changes.into_iter().map(|(path, old_mode, _new_mode)| (path, old_mode)));
+            app.jobs.enqueue(JobKind::Chmod, items, JobOrigin::Undo(app.undo.generation));
+            Ok(None)
+        },
+        Operation::Chown(changes) => {
+            let items = chown_items(changes.into_iter().map(|(path, old, _new)| (path, old)));
+            app.jobs.enqueue(JobKind::Chown, items, JobOrigin::Undo(app.undo.generation));
+            Ok(None)
+        },
+        // Removing a link leaves its target alone
+        Operation::Link { path, target, symbolic } => match fs::remove_file(&path) {
+            Ok(()) => Ok(Some(Operation::Link { path, target, symbolic })),
+            Err(err) => Err(format!("Unable to remove the link {}: {err}", path.to_string_lossy())),
+        },
+    };
+    match result {
+        Ok(redo_operation) => {
+            if let Some(redo_operation) = redo_operation {
+                app.undo.redo.push(redo_operation);
+            }
+            app.log_panel.set_log(format!("Undone: {description}"));
+        },
+        Err(err) => {
+            app.undo.undo.push(operation);
+            app.log_panel.set_log(err);
+        },
+    }
+    reload_explorers(app);
+}
+
+/// Does the last undone operation again
+pub fn redo(app: &mut App) {
+    let Some(operation) = app.undo.redo.pop() else {
+        app.log_panel.set_log(String::from("Nothing to redo"));
+        return;
+    };
+    let description = operation.describe();
+    // Kept to be put back when it fails
+    let result: Result<Option<Operation>, String> = match operation.clone() {
+        Operation::Irreversible(_) => Ok(None),
+        Operation::Create { path, is_dir } => {
+            let created = if is_dir { fs::create_dir(&path) } else { File::create_new(&path).map(|_| ()) };
+            match created {
+                Ok(()) => Ok(Some(Operation::Create { path, is_dir })),
+                Err(err) => Err(format!("Unable to create {}: {err}", path.to_string_lossy())),
+            }
+        },
+        Operation::Rename(pairs) => Ok(Some(Operation::Rename(rename_pairs(&pairs)))),
+        Operation::Move(pairs) | Operation::Copy(pairs) if pairs.is_empty() => Ok(None),
+        Operation::Move(pairs) => {
+            app.jobs.enqueue(JobKind::Move, job_items(pairs), JobOrigin::Redo);
+            Ok(None)
+        },
+        Operation::Copy(pairs) => {
+            app.jobs.enqueue(JobKind::Copy, job_items(pairs), JobOrigin::Redo);
+            Ok(None)
+        },
+        Operation::Trash(paths) => move_to_trash(paths).map(|trashed| Some(Operation::Trash(trashed))),
+        Operation::Chmod(changes) => {
+            let items = chmod_items(changes.into_iter().map(|(path, _old_mode, new_mode)| (path, new_mode)));
+            app.jobs.enqueue(JobKind::Chmod, items, JobOrigin::Redo);
+            Ok(None)
+        },
+        Operation::Chown(changes) => {
+            let items = chown_items(changes.into_iter().map(|(path, _old, new)| (path, new)));
+            app.jobs.enqueue(JobKind::Chown, items, JobOrigin::Redo);
+            Ok(None)
+        },
+        Operation::Link { path, target, symbolic } => match create_link(&target, &path, symbolic) {
+            Ok(()) => Ok(Some(Operation::Link { path, target, symbolic })),
+            Err(err) => Err(format!("Unable to create the link {}: {err}", path.to_string_lossy())),
+        },
+    };
+    match result {
+        Ok(undo_operation) => {
+            if let Some(undo_operation) = undo_operation {
+                app.undo.undo.push(undo_operation);
+            }
+            app.log_panel.set_log(format!("Redone: {description}"));
+        },
+        Err(err) => {
+            app.undo.redo.push(operation);
+            app.log_panel.set_log(err);
+        },
+    }
+    reload_explorers(app);
+}
+
+fn reload_explorers(app: &mut App) {
+    app.explorer.reload();
+    if let Some(other) = app.other_pane.as_mut() {
+        other.explorer.reload();
+    }
+}
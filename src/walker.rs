@@ -0,0 +1,32 @@
+use std::{fs, path::{Path, PathBuf}, sync::atomic::{AtomicBool, Ordering}};
+
+use hf::is_hidden;
+
+/// Walks the tree under `root` depth first without following symlinks.
+/// `on_entry` is called with the path of every entry and whether it is a directory.
+/// The walk stops as soon as `cancel` is set.
+pub fn walk_tree<F>(root: &Path, include_hidden: bool, cancel: &AtomicBool, mut on_entry: F)
+where
+    F: FnMut(&Path, bool),
+{
+    let mut stack: Vec<PathBuf> = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let Ok(read_dir) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let entry_path = entry.path();
+            if !include_hidden && is_hidden(&entry_path).unwrap_or(false) {
+                continue;
+            }
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            on_entry(&entry_path, is_dir);
+            if is_dir {
+                stack.push(entry_path);
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use notify::{
+    EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    event::ModifyKind,
+};
+
+use crate::AppEvent;
+
+/// Changes closer together than this are reported together
+const DEBOUNCE_TIME: Duration = Duration::from_millis(250);
+/// A directory that keeps changing is still refreshed this often
+const MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// Watches the directories shown in the Explorer panes and sends `AppEvent::DirChanged` when entries
+/// are created, removed or renamed in them
+pub struct DirWatcher {
+    watcher: Option<RecommendedWatcher>,
+    watched: Vec<PathBuf>,
+}
+
+// Gathers the changed directories of a burst of changes and sends each of them once
+fn debounce(changes_rx: mpsc::Receiver<PathBuf>, event_tx: Sender<AppEvent>) {
+    while let Ok(first) = changes_rx.recv() {
+        let mut changed: HashSet<PathBuf> = HashSet::from([first]);
+        let started = Instant::now();
+        while started.elapsed() < MAX_DELAY {
+            match changes_rx.recv_timeout(DEBOUNCE_TIME) {
+                Ok(dir_path) => {
+                    changed.insert(dir_path);
+                },
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        for dir_path in changed {
+            if event_tx.send(AppEvent::DirChanged(dir_path)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl DirWatcher {
+    pub fn new(event_tx: Sender<AppEvent>) -> DirWatcher {
+        let (changes_tx, changes_rx) = mpsc::channel::<PathBuf>();
+        let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))) {
+                return;
+            }
+            for path in event.paths {
+                if let Some(dir_path) = path.parent() {
+                    let _ = changes_tx.send(dir_path.to_path_buf());
+                }
+            }
+        })
+        .ok();
+        if watcher.is_some() {
+            thread::spawn(move || debounce(changes_rx, event_tx));
+        }
+        DirWatcher { watcher, watched: vec![] }
+    }
+
+    /// Watches exactly the given directories. Only does something when they differ from the ones watched.
+    pub fn watch(&mut self, mut dir_paths: Vec<PathBuf>) -> Result<(), String> {
+        dir_paths.sort();
+        if dir_paths == self.watched {
+            return Ok(());
+        }
+        let Some(watcher) = self.watcher.as_mut() else {
+            return Ok(());
+        };
+        for dir_path in &self.watched {
+            let _ = watcher.unwatch(dir_path);
+        }
+        let mut failed: Vec<String> = vec![];
+        for dir_path in &dir_paths {
+            if let Err(err) = watcher.watch(dir_path, RecursiveMode::NonRecursive) {
+                failed.push(format!("{}: {err}", dir_path.to_string_lossy()));
+            }
+        }
+        self.watched = dir_paths;
+        if !failed.is_empty() {
+            return Err(format!("Changes on disk won't be shown for {}", failed.join(", ")));
+        }
+        Ok(())
+    }
+}